@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ucsf_nmr::{Lineshape, PeakFitter, PeakPicker, Threshold, UcsfFile};
+
+fn fitting(c: &mut Criterion) {
+    let contents = include_bytes!("../tests/data/15n_hsqc.ucsf");
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    let picker = PeakPicker::new().positive(Threshold::NoiseMultiple(20.0));
+    let positions: Vec<_> = file
+        .pick_peaks(&picker)
+        .unwrap()
+        .into_iter()
+        .map(|peak| peak.ppms)
+        .collect();
+
+    let mut group = c.benchmark_group("fitting");
+    group.sample_size(10);
+    group.bench_function("fit_peaks_pseudo_voigt", |b| {
+        let fitter = PeakFitter::new(Lineshape::PseudoVoigt);
+        b.iter(|| black_box(file.fit_peaks(&positions, &fitter).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fitting);
+criterion_main!(benches);
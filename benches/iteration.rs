@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ucsf_nmr::{UcsfFile, UcsfView};
+
+fn iteration(c: &mut Criterion) {
+    let contents = include_bytes!("../tests/data/Nhsqc_highres_600MHz.ucsf");
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    c.bench_function("iter_with_abolute_pos", |b| {
+        b.iter(|| {
+            let mut sum = 0usize;
+            for tile in file.tiles() {
+                for (pos, value) in tile.iter_with_abolute_pos() {
+                    sum += pos[0] + pos[1] + value as usize;
+                }
+            }
+            black_box(sum)
+        })
+    });
+
+    c.bench_function("iter_with_abolute_pos_2d", |b| {
+        b.iter(|| {
+            let mut sum = 0usize;
+            for tile in file.tiles() {
+                for ((i_axis_1, i_axis_2), value) in tile.iter_with_abolute_pos().as_2d() {
+                    sum += i_axis_1 + i_axis_2 + value as usize;
+                }
+            }
+            black_box(sum)
+        })
+    });
+
+    c.bench_function("iter_with_abolute_pos_next_with_pos", |b| {
+        b.iter(|| {
+            let mut sum = 0usize;
+            for tile in file.tiles() {
+                let mut values = tile.iter_with_abolute_pos();
+                while let Some((pos, value)) = values.next_with_pos() {
+                    sum += pos[0] + pos[1] + value as usize;
+                }
+            }
+            black_box(sum)
+        })
+    });
+
+    let (_, view) = UcsfView::parse(&contents[..]).expect("Failed parsing");
+    c.bench_function("view_iter_with_abolute_pos", |b| {
+        b.iter(|| {
+            let mut sum = 0usize;
+            for tile in view.tiles() {
+                for (pos, value) in tile.iter_with_abolute_pos() {
+                    sum += pos[0] + pos[1] + value as usize;
+                }
+            }
+            black_box(sum)
+        })
+    });
+
+    c.bench_function("tiles", |b| {
+        b.iter(|| black_box(file.tiles().map(|tile| tile.data().len()).sum::<usize>()))
+    });
+
+    c.bench_function("data_continous", |b| {
+        b.iter(|| black_box(file.data_continous()))
+    });
+}
+
+criterion_group!(benches, iteration);
+criterion_main!(benches);
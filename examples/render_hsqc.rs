@@ -0,0 +1,21 @@
+use ucsf_nmr::{Colormap, RenderOptions, RenderStyle, UcsfFile};
+
+pub fn main() {
+    let contents = include_bytes!("../tests/data/15n_hsqc.ucsf");
+
+    let (_, contents) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    let options = RenderOptions::new(RenderStyle::AutoContour).title("15N HSQC");
+    contents
+        .render_png("examples/output/hsqc_contours.png", &options)
+        .expect("Failed rendering contours");
+
+    let (_, max) = contents.bounds();
+    let options = RenderOptions::new(RenderStyle::Image)
+        .colormap(Colormap::Greys)
+        .value_range(0.0, max / 10.0)
+        .title("15N HSQC");
+    contents
+        .render_png("examples/output/hsqc_image.png", &options)
+        .expect("Failed rendering image");
+}
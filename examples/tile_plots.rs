@@ -13,10 +13,7 @@ pub fn main() {
 
     let root = BitMapBackend::new(
         "examples/output/all_tiles.png",
-        (
-            contents.axis_data_points(0) as u32,
-            contents.axis_data_points(1) as u32,
-        ),
+        (contents.axis_data_points(0), contents.axis_data_points(1)),
     )
     .into_drawing_area();
 
@@ -11,12 +11,8 @@ pub fn main() {
 
     let (_, contents) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
 
-    let drawing_area_size = (
-        contents.axis_data_points(1) as u32,
-        contents.axis_data_points(2) as u32,
-    );
+    let drawing_area_size = (contents.axis_data_points(1), contents.axis_data_points(2));
     let image_paths: Vec<_> = (0..contents.axis_data_points(0))
-        .into_iter()
         .map(move |slice| std::path::PathBuf::from(format!("examples/output/3d_{:04}.png", slice)))
         .collect();
     let areas: Vec<_> = image_paths
@@ -0,0 +1,170 @@
+//! Arithmetic between spectra with matching axes.
+use crate::{tiled_data_from_fn, AxisHeader, TileLayout, UcsfError, UcsfFile};
+
+/// Maximum relative difference of the spectrometer frequencies and spectral widths of matching
+/// axes.
+const RELATIVE_TOLERANCE: f64 = 1e-4;
+/// Maximum difference of the centers of matching axes, as a fraction of a data point.
+const CENTER_TOLERANCE: f64 = 1e-2;
+
+impl AxisHeader {
+    /// Returns the first property in which the axis doesn't match `other`, if any.
+    fn mismatch(&self, other: &AxisHeader) -> Option<&'static str> {
+        let relative_difference =
+            |a: f32, b: f32| (a as f64 - b as f64).abs() / (a as f64).abs().max(f64::MIN_POSITIVE);
+        let center_difference = (self.center as f64 - other.center as f64).abs();
+
+        if self.nucleus_name.trim() != other.nucleus_name.trim() {
+            Some("nucleus")
+        } else if self.data_points != other.data_points {
+            Some("data points")
+        } else if relative_difference(self.frequency, other.frequency) > RELATIVE_TOLERANCE {
+            Some("spectrometer frequency")
+        } else if relative_difference(self.spectral_width, other.spectral_width)
+            > RELATIVE_TOLERANCE
+        {
+            Some("spectral width")
+        } else if center_difference > CENTER_TOLERANCE * self.ppm_per_point().abs() {
+            Some("center")
+        } else {
+            None
+        }
+    }
+}
+
+impl UcsfFile {
+    /// Check that the axes of `other` match the axes of this spectrum, so that the data points
+    /// at the same indices lie at the same ppm positions.
+    ///
+    /// Nucleus and amount of data points have to be equal, while spectrometer frequency, spectral
+    /// width and center may differ slightly. Tile sizes may differ.
+    pub fn check_compatible(&self, other: &UcsfFile) -> Result<(), UcsfError> {
+        if self.axis_headers.len() != other.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: other.axis_headers.len(),
+            });
+        }
+        for (axis, (a, b)) in self
+            .axis_headers
+            .iter()
+            .zip(&other.axis_headers)
+            .enumerate()
+        {
+            if let Some(property) = a.mismatch(b) {
+                return Err(UcsfError::IncompatibleAxis { axis, property });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add `other` to this spectrum.
+    ///
+    /// See [`UcsfFile::weighted_sum`] for details.
+    pub fn add(&self, other: &UcsfFile) -> Result<UcsfFile, UcsfError> {
+        UcsfFile::weighted_sum(&[(self, 1.0), (other, 1.0)])
+    }
+
+    /// Subtract `other` from this spectrum, e.g. to get a difference spectrum.
+    ///
+    /// See [`UcsfFile::weighted_sum`] for details.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use ucsf_nmr::UcsfFile;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+    /// #   let (_remaining_bytes, apo) = UcsfFile::parse(&file_bytes)?;
+    /// #   let holo = apo.scale(0.5);
+    /// let difference = holo.subtract(&apo)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subtract(&self, other: &UcsfFile) -> Result<UcsfFile, UcsfError> {
+        UcsfFile::weighted_sum(&[(self, 1.0), (other, -1.0)])
+    }
+
+    /// Multiply all values of the spectrum by `factor`.
+    pub fn scale(&self, factor: f32) -> UcsfFile {
+        UcsfFile {
+            header: self.header.clone(),
+            axis_headers: self.axis_headers.clone(),
+            data: self.data.iter().map(|value| value * factor).collect(),
+            imaginary_data: self
+                .imaginary_data
+                .as_ref()
+                .map(|data| data.iter().map(|value| value * factor).collect()),
+        }
+    }
+
+    /// Sum up the spectra, each multiplied by its weight.
+    ///
+    /// All spectra have to be compatible with the first one (see
+    /// [`UcsfFile::check_compatible`]), whose headers and tile layout are used for the result.
+    /// The result only contains imaginary data if all spectra contain it.
+    pub fn weighted_sum(spectra: &[(&UcsfFile, f32)]) -> Result<UcsfFile, UcsfError> {
+        let (first, _) = spectra.first().ok_or(UcsfError::NoSpectra)?;
+        for (spectrum, _) in &spectra[1..] {
+            first.check_compatible(spectrum)?;
+        }
+
+        let imaginary = spectra
+            .iter()
+            .all(|(spectrum, _)| spectrum.imaginary_data.is_some());
+        let mut header = first.header.clone();
+        header.components = match imaginary {
+            true => 2,
+            false => 1,
+        };
+
+        Ok(UcsfFile {
+            header,
+            axis_headers: first.axis_headers.clone(),
+            data: weighted_sum_data(first, spectra, false),
+            imaginary_data: match imaginary {
+                true => Some(weighted_sum_data(first, spectra, true)),
+                false => None,
+            },
+        })
+    }
+}
+
+/// Sum up the real or `imaginary` data of the spectra, each multiplied by its weight, in the
+/// tile layout of `first`.
+fn weighted_sum_data(first: &UcsfFile, spectra: &[(&UcsfFile, f32)], imaginary: bool) -> Vec<f32> {
+    fn data(spectrum: &UcsfFile, imaginary: bool) -> &[f32] {
+        match imaginary {
+            false => &spectrum.data,
+            true => spectrum.imaginary_data.as_ref().unwrap(),
+        }
+    }
+
+    let same_layout = spectra
+        .iter()
+        .all(|(spectrum, _)| spectrum.axis_tile_sizes() == first.axis_tile_sizes());
+    if same_layout {
+        let mut sum = vec![0f32; first.data.len()];
+        for (spectrum, weight) in spectra {
+            for (sum, value) in sum.iter_mut().zip(data(spectrum, imaginary)) {
+                *sum += weight * value;
+            }
+        }
+        return sum;
+    }
+
+    let layouts: Vec<_> = spectra
+        .iter()
+        .map(|(spectrum, _)| TileLayout::new(&spectrum.axis_headers))
+        .collect();
+    tiled_data_from_fn(&first.axis_headers, |indices| {
+        spectra
+            .iter()
+            .zip(&layouts)
+            .map(|((spectrum, weight), layout)| {
+                weight * data(spectrum, imaginary)[layout.position(indices)]
+            })
+            .sum()
+    })
+}
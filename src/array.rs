@@ -0,0 +1,76 @@
+//! Integration with [`ndarray`] (requires the `ndarray` feature).
+use ndarray::{ArrayD, ArrayViewD, Axis, IxDyn, Slice};
+
+use crate::{AxisHeader, Tile, UcsfError, UcsfFile};
+
+impl UcsfFile {
+    /// Construct an N-dimensional array from the data, with one array axis per spectrum axis.
+    pub fn to_array(&self) -> ArrayD<f32> {
+        ArrayD::from_shape_vec(IxDyn(&self.axis_sizes()), self.data_continous())
+            .expect("Continous data has to match the axis sizes")
+    }
+
+    /// Construct an N-dimensional array from the imaginary data.
+    ///
+    /// Returns `None` if the file contains no imaginary data.
+    pub fn to_imaginary_array(&self) -> Option<ArrayD<f32>> {
+        let data = self.imaginary_data_continous()?;
+        Some(
+            ArrayD::from_shape_vec(IxDyn(&self.axis_sizes()), data)
+                .expect("Continous data has to match the axis sizes"),
+        )
+    }
+
+    /// Construct a spectrum from an N-dimensional array and one axis header per array axis.
+    ///
+    /// The amount of data points of each axis header has to match the length of the array
+    /// along that axis. Tile sizes are chosen like in [`UcsfFileBuilder`](crate::UcsfFileBuilder).
+    pub fn from_array(array: ArrayD<f32>, axes: Vec<AxisHeader>) -> Result<UcsfFile, UcsfError> {
+        if array.ndim() != axes.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: array.ndim(),
+                actual: axes.len(),
+            });
+        }
+        let mismatched_axis = array
+            .shape()
+            .iter()
+            .zip(&axes)
+            .position(|(len, axis)| *len != axis.data_points as usize);
+        if let Some(axis) = mismatched_axis {
+            return Err(UcsfError::InvalidAxisSize(axis));
+        }
+
+        // Iteration happens in logical order, which is the continous layout
+        let data: Vec<f32> = array.iter().copied().collect();
+        axes.into_iter()
+            .fold(UcsfFile::builder(data), |builder, axis| builder.axis(axis))
+            .build()
+    }
+}
+
+impl<'a> Tile<'a> {
+    /// Borrow the data of the tile (without padding) as an N-dimensional array view.
+    ///
+    /// The view has the shape [`Tile::axis_lengths`], with indices relative to
+    /// [`Tile::axis_starts`].
+    pub fn array_view(&self) -> ArrayViewD<'a, f32> {
+        self.view_without_padding(self.data)
+    }
+
+    /// Borrow the imaginary data of the tile (without padding) as an N-dimensional array view.
+    ///
+    /// Returns `None` if the file contains no imaginary data.
+    pub fn imaginary_array_view(&self) -> Option<ArrayViewD<'a, f32>> {
+        Some(self.view_without_padding(self.imaginary_data?))
+    }
+
+    fn view_without_padding(&self, data: &'a [f32]) -> ArrayViewD<'a, f32> {
+        let mut view = ArrayViewD::from_shape(IxDyn(&self.padded_axis_lengths), data)
+            .expect("Tile data has to match the tile size");
+        for (axis, length) in self.axis_lengths.iter().enumerate() {
+            view.slice_axis_inplace(Axis(axis), Slice::from(0..*length));
+        }
+        view
+    }
+}
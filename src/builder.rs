@@ -0,0 +1,157 @@
+//! Construction of spectra from data in memory.
+use crate::{multi_dim_position, tiled_data_from_fn, AxisHeader, Header, UcsfError, UcsfFile};
+
+/// Default maximum amount of data points in a tile (= 32 KB of f32 values).
+const DEFAULT_TILE_DATA_POINTS: usize = 8192;
+
+/// Builder for a [`UcsfFile`] from data layed out continously per-axis (the layout returned by
+/// [`UcsfFile::data_continous`]).
+///
+/// ```
+/// # use ucsf_nmr::{AxisHeader, UcsfFile};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// let data = vec![0f32; 256 * 512];
+/// let ucsf_file = UcsfFile::builder(data)
+///     .axis(AxisHeader::new("15N", 256, 60.833, 1824.818, 117.043))
+///     .axis(AxisHeader::new("1H", 512, 600.283, 7211.538, 4.7))
+///     .build()?;
+/// assert_eq!(ucsf_file.axis_tile_sizes(), vec![64, 128]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UcsfFileBuilder {
+    data: Vec<f32>,
+    imaginary_data: Option<Vec<f32>>,
+    axis_headers: Vec<AxisHeader>,
+    tile_sizes: Option<Vec<usize>>,
+}
+
+impl UcsfFileBuilder {
+    pub fn new(data: Vec<f32>) -> Self {
+        Self {
+            data,
+            imaginary_data: None,
+            axis_headers: vec![],
+            tile_sizes: None,
+        }
+    }
+
+    /// Add the next axis.
+    ///
+    /// The tile size of the axis header is ignored, see [`UcsfFileBuilder::tile_sizes`].
+    pub fn axis(mut self, axis_header: AxisHeader) -> Self {
+        self.axis_headers.push(axis_header);
+        self
+    }
+
+    /// Set the imaginary part of the data, in the same layout as the real part.
+    pub fn imaginary_data(mut self, imaginary_data: Vec<f32>) -> Self {
+        self.imaginary_data = Some(imaginary_data);
+        self
+    }
+
+    /// Set the tile size along each axis.
+    ///
+    /// If no tile sizes are set, they are chosen so that a tile contains at most 8192 data
+    /// points (32 KB), by repeatedly halving the largest tile size.
+    pub fn tile_sizes(mut self, tile_sizes: &[usize]) -> Self {
+        self.tile_sizes = Some(tile_sizes.to_vec());
+        self
+    }
+
+    fn default_tile_sizes(axis_sizes: &[usize]) -> Vec<usize> {
+        let mut tile_sizes = axis_sizes.to_vec();
+        while tile_sizes.iter().product::<usize>() > DEFAULT_TILE_DATA_POINTS {
+            let largest = tile_sizes
+                .iter()
+                .enumerate()
+                .max_by_key(|(axis, tile_size)| (**tile_size, std::cmp::Reverse(*axis)))
+                .map(|(axis, _)| axis)
+                .unwrap();
+            tile_sizes[largest] = tile_sizes[largest].div_ceil(2);
+        }
+        tile_sizes
+    }
+
+    /// Build the [`UcsfFile`] by re-arranging the data into tiles.
+    pub fn build(self) -> Result<UcsfFile, UcsfError> {
+        if self.axis_headers.is_empty() {
+            return Err(UcsfError::NoAxes);
+        }
+        let axis_sizes: Vec<_> = self
+            .axis_headers
+            .iter()
+            .map(|axis| axis.data_points as usize)
+            .collect();
+        if let Some(axis) = axis_sizes.iter().position(|size| *size == 0) {
+            return Err(UcsfError::InvalidAxisSize(axis));
+        }
+
+        let tile_sizes = match self.tile_sizes {
+            Some(tile_sizes) => tile_sizes,
+            None => Self::default_tile_sizes(&axis_sizes),
+        };
+        if tile_sizes.len() != axis_sizes.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: axis_sizes.len(),
+                actual: tile_sizes.len(),
+            });
+        }
+        if let Some(axis) = tile_sizes.iter().position(|tile_size| *tile_size == 0) {
+            return Err(UcsfError::InvalidAxisSize(axis));
+        }
+
+        let data_points = axis_sizes.iter().product();
+        for data in Some(&self.data).into_iter().chain(&self.imaginary_data) {
+            if data.len() != data_points {
+                return Err(UcsfError::DataSizeMismatch {
+                    expected: data_points,
+                    actual: data.len(),
+                });
+            }
+        }
+
+        let axis_headers: Vec<_> = self
+            .axis_headers
+            .into_iter()
+            .zip(&tile_sizes)
+            .map(|(axis_header, tile_size)| AxisHeader {
+                tile_size: *tile_size as u32,
+                ..axis_header
+            })
+            .collect();
+
+        let to_tiled = |continous: &[f32]| {
+            tiled_data_from_fn(&axis_headers, |indices| {
+                continous[multi_dim_position(&axis_sizes, indices)]
+            })
+        };
+        let data = to_tiled(&self.data);
+        let imaginary_data = self.imaginary_data.as_deref().map(to_tiled);
+
+        Ok(UcsfFile {
+            header: Header {
+                dimensions: axis_headers.len() as u8,
+                components: match imaginary_data {
+                    Some(_) => 2,
+                    None => 1,
+                },
+                format_version: 2,
+                remainder: [0u8].repeat(166),
+            },
+            axis_headers,
+            data,
+            imaginary_data,
+        })
+    }
+}
+
+impl UcsfFile {
+    /// Returns a [`UcsfFileBuilder`] for constructing a spectrum from `data` layed out
+    /// continously per-axis.
+    pub fn builder(data: Vec<f32>) -> UcsfFileBuilder {
+        UcsfFileBuilder::new(data)
+    }
+}
@@ -0,0 +1,338 @@
+//! Contour lines of 2D spectra.
+use std::collections::HashMap;
+
+use crate::{UcsfError, UcsfFile};
+
+/// Multiple of the noise level at which the lowest automatic contour level is placed.
+const DEFAULT_NOISE_MULTIPLE: f32 = 5.0;
+/// Factor between consecutive automatic contour levels.
+const DEFAULT_LEVEL_FACTOR: f32 = 1.4;
+
+/// Contour line at a single level, as returned by [`UcsfFile::contours`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub level: f32,
+    /// ppm positions along the first and second axis of the points on the line.
+    pub points: Vec<(f64, f64)>,
+    /// Whether the line is closed, in which case the last point connects back to the first one
+    /// (without being repeated). Open lines start and end at the border of the spectrum.
+    pub closed: bool,
+}
+
+/// Geometric series of contour levels, as set in Sparky's contour dialog.
+///
+/// Levels for negative signals are described by a negative `lowest` level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourLevels {
+    /// Level closest to zero.
+    pub lowest: f32,
+    /// Amount of levels.
+    pub count: usize,
+    /// Factor between consecutive levels.
+    pub factor: f32,
+}
+
+impl ContourLevels {
+    /// Create the series starting at `lowest`, with as many levels as fit up to `highest`
+    /// (or down to, for negative levels).
+    ///
+    /// Returns `None` if `lowest` and `highest` have different signs, `highest` is closer to
+    /// zero than `lowest`, or `factor` isn't larger than 1.
+    pub fn up_to(lowest: f32, highest: f32, factor: f32) -> Option<Self> {
+        let ratio = highest / lowest;
+        let valid = ratio.is_finite() && ratio >= 1.0 && factor > 1.0;
+        if !valid {
+            return None;
+        }
+
+        Some(Self {
+            lowest,
+            // Tolerance, so that a level exactly at `highest` isn't lost to rounding
+            count: (ratio.ln() / factor.ln() + 1e-6).floor() as usize + 1,
+            factor,
+        })
+    }
+
+    /// All levels of the series, starting at the lowest one.
+    pub fn levels(&self) -> Vec<f32> {
+        (0..self.count)
+            .map(|level| self.lowest * self.factor.powi(level as i32))
+            .collect()
+    }
+}
+
+/// Contour levels for positive and negative signals, as returned by
+/// [`UcsfFile::auto_contour_levels`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoContourLevels {
+    /// Levels for positive signals, if the spectrum contains any above the lowest level.
+    pub positive: Option<ContourLevels>,
+    /// Levels for negative signals, if the spectrum contains any below the lowest level.
+    pub negative: Option<ContourLevels>,
+}
+
+impl AutoContourLevels {
+    /// All positive and negative levels, e.g. to be passed to [`UcsfFile::contours`].
+    pub fn levels(&self) -> Vec<f32> {
+        self.positive
+            .iter()
+            .chain(&self.negative)
+            .flat_map(ContourLevels::levels)
+            .collect()
+    }
+}
+
+/// Side of a cell of four neighbouring data points.
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl UcsfFile {
+    /// Compute Sparky-style contour levels from the noise level and the range of the data.
+    ///
+    /// The lowest level is placed at 5 times the noise level estimated by
+    /// [`UcsfFile::noise_level`], with a factor of 1.4 between consecutive levels, up to the
+    /// maximum (or down to the minimum) given by [`UcsfFile::bounds`]. See
+    /// [`UcsfFile::contour_levels`] to choose the multiple of the noise level and the factor.
+    pub fn auto_contour_levels(&self) -> AutoContourLevels {
+        self.contour_levels(DEFAULT_NOISE_MULTIPLE, DEFAULT_LEVEL_FACTOR)
+    }
+
+    /// Compute contour levels starting at `noise_multiple` times the noise level, with `factor`
+    /// between consecutive levels.
+    pub fn contour_levels(&self, noise_multiple: f32, factor: f32) -> AutoContourLevels {
+        let lowest = match self.noise_level() {
+            Some(noise_level) if noise_level > 0.0 => noise_multiple.abs() * noise_level,
+            _ => {
+                return AutoContourLevels {
+                    positive: None,
+                    negative: None,
+                }
+            }
+        };
+        let (min, max) = self.bounds();
+
+        AutoContourLevels {
+            positive: ContourLevels::up_to(lowest, max, factor),
+            negative: ContourLevels::up_to(-lowest, min, factor),
+        }
+    }
+
+    /// Generate the contour lines of a 2D spectrum at each of the `levels` via marching squares.
+    ///
+    /// Positive and negative levels are both supported. Saddle points are resolved by the average
+    /// of the surrounding data points. Data points that are not finite don't produce contours.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use ucsf_nmr::UcsfFile;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+    /// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+    /// let levels = ucsf_file.auto_contour_levels();
+    /// for contour in ucsf_file.contours(&levels.levels())? {
+    ///     format!("{} : {:?}", contour.level, contour.points);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contours(&self, levels: &[f32]) -> Result<Vec<Contour>, UcsfError> {
+        if self.axis_headers.len() != 2 {
+            return Err(UcsfError::DimensionMismatch {
+                expected: 2,
+                actual: self.axis_headers.len(),
+            });
+        }
+
+        let (rows, columns) = (
+            self.axis_headers[0].data_points as usize,
+            self.axis_headers[1].data_points as usize,
+        );
+        let data = self.data_continous();
+        let contours = levels
+            .iter()
+            .flat_map(|level| {
+                contour_lines(&data, rows, columns, *level)
+                    .into_iter()
+                    .map(move |(points, closed)| (*level, points, closed))
+            })
+            .map(|(level, points, closed)| Contour {
+                level,
+                points: points
+                    .into_iter()
+                    .map(|(row, column)| {
+                        (
+                            self.axis_headers[0].index_to_ppm(row),
+                            self.axis_headers[1].index_to_ppm(column),
+                        )
+                    })
+                    .collect(),
+                closed,
+            })
+            .collect();
+
+        Ok(contours)
+    }
+}
+
+/// Run marching squares on the continous `data` with `rows` x `columns` data points.
+///
+/// Returns the lines in (fractional) indices, and whether they are closed.
+fn contour_lines(
+    data: &[f32],
+    rows: usize,
+    columns: usize,
+    level: f32,
+) -> Vec<(Vec<(f64, f64)>, bool)> {
+    let value = |row: usize, column: usize| data[row * columns + column];
+
+    // Every crossing of the level lies on the edge between two neighbouring data points, which
+    // is identified by its first data point and whether it is horizontal (along the columns).
+    let edge_key = |row: usize, column: usize, horizontal: bool| {
+        (row * columns + column) * 2 + horizontal as usize
+    };
+    let crossing = |row: usize, column: usize, horizontal: bool| {
+        let (end_row, end_column) = match horizontal {
+            true => (row, column + 1),
+            false => (row + 1, column),
+        };
+        let (start, end) = (value(row, column), value(end_row, end_column));
+        let t = ((level - start) / (end - start)) as f64;
+        match horizontal {
+            true => (row as f64, column as f64 + t),
+            false => (row as f64 + t, column as f64),
+        }
+    };
+
+    let mut points: HashMap<usize, (f64, f64)> = HashMap::new();
+    let mut segments: Vec<(usize, usize)> = vec![];
+    for row in 0..rows.saturating_sub(1) {
+        for column in 0..columns.saturating_sub(1) {
+            // Corners in clockwise order, starting at the top left
+            let corners = [
+                value(row, column),
+                value(row, column + 1),
+                value(row + 1, column + 1),
+                value(row + 1, column),
+            ];
+            if corners.iter().any(|corner| !corner.is_finite()) {
+                continue;
+            }
+            let above: Vec<_> = corners.iter().map(|corner| *corner >= level).collect();
+
+            let edge = |side: Side| match side {
+                Side::Top => (row, column, true),
+                Side::Right => (row, column + 1, false),
+                Side::Bottom => (row + 1, column, true),
+                Side::Left => (row, column, false),
+            };
+            let mut add_segment = |a: Side, b: Side| {
+                let (a, b) = (edge(a), edge(b));
+                let keys = (edge_key(a.0, a.1, a.2), edge_key(b.0, b.1, b.2));
+                points
+                    .entry(keys.0)
+                    .or_insert_with(|| crossing(a.0, a.1, a.2));
+                points
+                    .entry(keys.1)
+                    .or_insert_with(|| crossing(b.0, b.1, b.2));
+                segments.push(keys);
+            };
+
+            // Sides of the corners in clockwise order, so that a corner lies between the sides
+            // at the same index and the next one.
+            let corner_sides = [
+                (Side::Left, Side::Top),
+                (Side::Top, Side::Right),
+                (Side::Right, Side::Bottom),
+                (Side::Bottom, Side::Left),
+            ];
+            let crossed: Vec<_> = [Side::Top, Side::Right, Side::Bottom, Side::Left]
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| above[*i] != above[(i + 1) % 4])
+                .map(|(_, side)| *side)
+                .collect();
+            match crossed.len() {
+                2 => add_segment(crossed[0], crossed[1]),
+                4 => {
+                    // Saddle: cut off the corners that differ from the center of the cell
+                    let center = corners.iter().sum::<f32>() / 4.0 >= level;
+                    for (corner, (a, b)) in corner_sides.iter().enumerate() {
+                        if above[corner] != center {
+                            add_segment(*a, *b);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    join_segments(&segments, &points)
+}
+
+/// Join `segments` between crossings into polylines.
+fn join_segments(
+    segments: &[(usize, usize)],
+    points: &HashMap<usize, (f64, f64)>,
+) -> Vec<(Vec<(f64, f64)>, bool)> {
+    let mut edge_segments: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (segment, (a, b)) in segments.iter().enumerate() {
+        edge_segments.entry(*a).or_default().push(segment);
+        edge_segments.entry(*b).or_default().push(segment);
+    }
+
+    let mut visited = vec![false; segments.len()];
+    let walk = |start: usize, visited: &mut [bool]| {
+        let mut line = vec![points[&start]];
+        let mut edge = start;
+        while let Some(segment) = edge_segments[&edge]
+            .iter()
+            .copied()
+            .find(|segment| !visited[*segment])
+        {
+            visited[segment] = true;
+            let (a, b) = segments[segment];
+            edge = match a == edge {
+                true => b,
+                false => a,
+            };
+            line.push(points[&edge]);
+        }
+        (line, edge)
+    };
+
+    let mut lines = vec![];
+    // Open lines start at crossings that are only part of one segment (at the border)
+    let mut starts: Vec<_> = edge_segments
+        .iter()
+        .filter(|(_, segments)| segments.len() == 1)
+        .map(|(edge, _)| *edge)
+        .collect();
+    starts.sort_unstable();
+    for start in starts {
+        if visited[edge_segments[&start][0]] {
+            continue;
+        }
+        let (line, _) = walk(start, &mut visited);
+        lines.push((line, false));
+    }
+    for segment in 0..segments.len() {
+        if visited[segment] {
+            continue;
+        }
+        let (mut line, end) = walk(segments[segment].0, &mut visited);
+        let closed = end == segments[segment].0;
+        if closed {
+            line.pop();
+        }
+        lines.push((line, closed));
+    }
+
+    lines
+}
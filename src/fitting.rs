@@ -0,0 +1,668 @@
+//! Least-squares fitting of peak lineshapes.
+use std::f64::consts::{LN_2, PI};
+use std::ops::Range;
+
+use crate::{next_indices, TileLayout, UcsfError, UcsfFile};
+
+/// Default maximum amount of Levenberg-Marquardt iterations.
+const DEFAULT_MAX_ITERATIONS: usize = 200;
+/// Smallest allowed linewidth (in data points), which keeps a lineshape from collapsing.
+const MIN_LINEWIDTH: f64 = 0.1;
+/// Maximum amount of peaks that are fitted together. Larger clusters of overlapping peaks are
+/// split into several groups.
+const MAX_GROUP_PEAKS: usize = 8;
+
+/// Shape of a peak along each axis, as used by [`PeakFitter`].
+///
+/// All lineshapes are parametrized by their center and full width at half maximum, and have a
+/// height of 1 at their center. In more than one dimension, the lineshape of a peak is the
+/// product of the lineshapes along each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lineshape {
+    Gaussian,
+    Lorentzian,
+    /// Linear combination of a Lorentzian (with fraction `eta`) and a Gaussian of the same width.
+    ///
+    /// `eta` is fitted per peak.
+    PseudoVoigt,
+}
+
+impl Lineshape {
+    fn value(self, offset: f64, width: f64, eta: f64) -> f64 {
+        let x = 4.0 * offset * offset / (width * width);
+        let gaussian = (-LN_2 * x).exp();
+        let lorentzian = 1.0 / (1.0 + x);
+        match self {
+            Lineshape::Gaussian => gaussian,
+            Lineshape::Lorentzian => lorentzian,
+            Lineshape::PseudoVoigt => eta * lorentzian + (1.0 - eta) * gaussian,
+        }
+    }
+
+    /// Value and its partial derivatives with respect to the center, width and `eta`.
+    fn derivatives(self, offset: f64, width: f64, eta: f64) -> [f64; 4] {
+        let x = 4.0 * offset * offset / (width * width);
+        let gaussian = (-LN_2 * x).exp();
+        let lorentzian = 1.0 / (1.0 + x);
+        let (value, d_x, d_eta) = match self {
+            Lineshape::Gaussian => (gaussian, -LN_2 * gaussian, 0.0),
+            Lineshape::Lorentzian => (lorentzian, -lorentzian * lorentzian, 0.0),
+            Lineshape::PseudoVoigt => (
+                eta * lorentzian + (1.0 - eta) * gaussian,
+                -eta * lorentzian * lorentzian - (1.0 - eta) * LN_2 * gaussian,
+                lorentzian - gaussian,
+            ),
+        };
+        let d_center = d_x * -8.0 * offset / (width * width);
+        let d_width = d_x * -2.0 * x / width;
+        [value, d_center, d_width, d_eta]
+    }
+
+    /// Integral over the lineshape.
+    fn integral(self, width: f64, eta: f64) -> f64 {
+        let gaussian = width / 2.0 * (PI / LN_2).sqrt();
+        let lorentzian = PI * width / 2.0;
+        match self {
+            Lineshape::Gaussian => gaussian,
+            Lineshape::Lorentzian => lorentzian,
+            Lineshape::PseudoVoigt => eta * lorentzian + (1.0 - eta) * gaussian,
+        }
+    }
+}
+
+/// Settings for fitting peaks via [`UcsfFile::fit_peaks`].
+///
+/// ```
+/// # use std::fs;
+/// # use ucsf_nmr::{Lineshape, PeakFitter, PeakPicker, Threshold, UcsfFile};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+/// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+/// let picker = PeakPicker::new().positive(Threshold::NoiseMultiple(50.0));
+/// let positions: Vec<_> = ucsf_file
+///     .pick_peaks(&picker)?
+///     .into_iter()
+///     .map(|peak| peak.ppms)
+///     .collect();
+/// let fitter = PeakFitter::new(Lineshape::Lorentzian);
+/// for peak in ucsf_file.fit_peaks(&positions, &fitter)? {
+///     format!("{:?} : {:?} Hz", peak.ppms, peak.linewidths_hz);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PeakFitter {
+    lineshape: Lineshape,
+    radius: Option<Vec<usize>>,
+    max_iterations: usize,
+}
+
+impl PeakFitter {
+    pub fn new(lineshape: Lineshape) -> Self {
+        Self {
+            lineshape,
+            radius: None,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+        }
+    }
+
+    /// Set the radius (in data points) along each axis of the neighbourhood around each peak
+    /// that is taken into account.
+    ///
+    /// If no radius is set, it is chosen per peak from an estimate of its linewidth.
+    pub fn radius(mut self, radius: &[usize]) -> Self {
+        self.radius = Some(radius.to_vec());
+        self
+    }
+
+    /// Set the maximum amount of iterations of the optimization.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+}
+
+/// Peak fitted by [`UcsfFile::fit_peaks`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FittedPeak {
+    /// Refined ppm position.
+    pub ppms: Vec<f64>,
+    /// Full width at half maximum along each axis in Hz.
+    pub linewidths_hz: Vec<f64>,
+    pub height: f64,
+    /// Integral over the fitted lineshape, with the data points as unit along each axis.
+    pub volume: f64,
+    /// Fraction of the Lorentzian in a [`Lineshape::PseudoVoigt`], otherwise `None`.
+    pub eta: Option<f64>,
+    /// Root mean square of the residuals of the fit, over the neighbourhood of all peaks that
+    /// were fitted together with this one.
+    pub residual: f64,
+    /// Index of the group of overlapping peaks that were fitted together.
+    pub group: usize,
+    /// Whether the optimization converged within the maximum amount of iterations, without the
+    /// center or a linewidth ending up at the limit of its allowed range.
+    pub converged: bool,
+}
+
+/// Parameters of a single peak, with positions and widths in data points.
+#[derive(Debug, Clone)]
+struct PeakParameters {
+    height: f64,
+    centers: Vec<f64>,
+    widths: Vec<f64>,
+    eta: f64,
+}
+
+impl PeakParameters {
+    fn len(dimensions: usize, lineshape: Lineshape) -> usize {
+        1 + 2 * dimensions + (lineshape == Lineshape::PseudoVoigt) as usize
+    }
+
+    fn to_vec(&self, lineshape: Lineshape) -> Vec<f64> {
+        let mut params = vec![self.height];
+        params.extend(&self.centers);
+        params.extend(&self.widths);
+        if lineshape == Lineshape::PseudoVoigt {
+            params.push(self.eta);
+        }
+        params
+    }
+
+    fn from_slice(params: &[f64], dimensions: usize, lineshape: Lineshape) -> Self {
+        Self {
+            height: params[0],
+            centers: params[1..=dimensions].to_vec(),
+            widths: params[1 + dimensions..1 + 2 * dimensions].to_vec(),
+            eta: match lineshape {
+                Lineshape::PseudoVoigt => params[1 + 2 * dimensions],
+                _ => 0.0,
+            },
+        }
+    }
+
+    fn value(&self, lineshape: Lineshape, point: &[f64]) -> f64 {
+        self.centers
+            .iter()
+            .zip(&self.widths)
+            .zip(point)
+            .map(|((center, width), x)| lineshape.value(x - center, *width, self.eta))
+            .product::<f64>()
+            * self.height
+    }
+
+    /// Value at `point` and its partial derivatives with respect to all parameters, in the order
+    /// of [`PeakParameters::to_vec`].
+    ///
+    /// `shapes` is only used as buffer for the lineshapes along each axis.
+    fn value_and_gradient(
+        &self,
+        lineshape: Lineshape,
+        point: &[f64],
+        shapes: &mut Vec<[f64; 4]>,
+        gradient: &mut [f64],
+    ) -> f64 {
+        let dimensions = self.centers.len();
+        shapes.clear();
+        shapes.extend(
+            self.centers
+                .iter()
+                .zip(&self.widths)
+                .zip(point)
+                .map(|((center, width), x)| lineshape.derivatives(x - center, *width, self.eta)),
+        );
+
+        let shape: f64 = shapes.iter().map(|shape| shape[0]).product();
+        gradient[0] = shape;
+        let mut d_eta = 0.0;
+        for axis in 0..dimensions {
+            // Product of the lineshapes along all other axes
+            let others: f64 = shapes
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != axis)
+                .map(|(_, shape)| shape[0])
+                .product();
+            gradient[1 + axis] = self.height * others * shapes[axis][1];
+            gradient[1 + dimensions + axis] = self.height * others * shapes[axis][2];
+            d_eta += others * shapes[axis][3];
+        }
+        if lineshape == Lineshape::PseudoVoigt {
+            gradient[1 + 2 * dimensions] = self.height * d_eta;
+        }
+
+        self.height * shape
+    }
+
+    /// Lower and upper limit of all parameters, in the order of [`PeakParameters::to_vec`].
+    ///
+    /// The centers have to lie inside of the fitted `ranges` and the widths can be at most as
+    /// large as them.
+    fn limits(ranges: &[Range<usize>], lineshape: Lineshape) -> Vec<(f64, f64)> {
+        let mut limits = vec![(f64::NEG_INFINITY, f64::INFINITY)];
+        limits.extend(
+            ranges
+                .iter()
+                .map(|range| (range.start as f64, (range.end - 1) as f64)),
+        );
+        limits.extend(
+            ranges
+                .iter()
+                .map(|range| (MIN_LINEWIDTH, range.len() as f64)),
+        );
+        if lineshape == Lineshape::PseudoVoigt {
+            limits.push((0.0, 1.0));
+        }
+        limits
+    }
+
+    /// Whether a center or width lies at its limit as given by [`PeakParameters::limits`].
+    fn at_limit(&self, ranges: &[Range<usize>]) -> bool {
+        let center_at_limit = self.centers.iter().zip(ranges).any(|(center, range)| {
+            *center <= range.start as f64 || *center >= (range.end - 1) as f64
+        });
+        let width_at_limit = self
+            .widths
+            .iter()
+            .zip(ranges)
+            .any(|(width, range)| *width <= MIN_LINEWIDTH || *width >= range.len() as f64);
+        center_at_limit || width_at_limit
+    }
+}
+
+impl UcsfFile {
+    /// Fit lineshapes to the peaks at the ppm `positions` via least-squares on the real part of the
+    /// data.
+    ///
+    /// Peaks whose neighbourhoods overlap are fitted together, in groups of at most 8 peaks. Other
+    /// peaks that overlap with a group are kept fixed at their current estimate while fitting it.
+    /// The returned peaks are in the same order as `positions`.
+    pub fn fit_peaks(
+        &self,
+        positions: &[Vec<f64>],
+        fitter: &PeakFitter,
+    ) -> Result<Vec<FittedPeak>, UcsfError> {
+        let dimensions = self.axis_headers.len();
+        if let Some(radius) = &fitter.radius {
+            if radius.len() != dimensions {
+                return Err(UcsfError::DimensionMismatch {
+                    expected: dimensions,
+                    actual: radius.len(),
+                });
+            }
+        }
+        let indices = positions
+            .iter()
+            .map(|ppms| {
+                if ppms.len() != dimensions {
+                    return Err(UcsfError::DimensionMismatch {
+                        expected: dimensions,
+                        actual: ppms.len(),
+                    });
+                }
+                self.ppm_to_indices(ppms).ok_or(UcsfError::OutOfBounds)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let layout = TileLayout::new(&self.axis_headers);
+        let mut parameters: Vec<_> = indices
+            .iter()
+            .map(|indices| initial_parameters(&self.data, &layout, indices))
+            .collect();
+        let boxes: Vec<Vec<Range<usize>>> = indices
+            .iter()
+            .zip(&parameters)
+            .map(|(indices, initial)| {
+                indices
+                    .iter()
+                    .zip(&layout.axis_sizes)
+                    .enumerate()
+                    .map(|(axis, (index, size))| {
+                        let radius = match &fitter.radius {
+                            Some(radius) => radius[axis],
+                            None => (initial.widths[axis].ceil() as usize).max(2),
+                        };
+                        index.saturating_sub(radius)..(index + radius + 1).min(*size)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut fitted = vec![None; positions.len()];
+        for (group, members) in overlapping_groups(&boxes).into_iter().enumerate() {
+            // Bounding box of the neighbourhoods of all peaks in the group
+            let ranges: Vec<_> = (0..dimensions)
+                .map(|axis| {
+                    let start = members.iter().map(|m| boxes[*m][axis].start).min().unwrap();
+                    let end = members.iter().map(|m| boxes[*m][axis].end).max().unwrap();
+                    start..end
+                })
+                .collect();
+            let neighbours: Vec<_> = (0..boxes.len())
+                .filter(|peak| !members.contains(peak) && overlaps(&boxes[*peak], &ranges))
+                .map(|peak| &parameters[peak])
+                .collect();
+            let mut values: Vec<f64> = self
+                .block_continous(&ranges)
+                .into_iter()
+                .map(|value| value as f64)
+                .collect();
+            if !neighbours.is_empty() {
+                let mut point: Vec<_> = ranges.iter().map(|range| range.start).collect();
+                let mut coordinates = vec![0f64; dimensions];
+                for value in values.iter_mut() {
+                    for (coordinate, index) in coordinates.iter_mut().zip(&point) {
+                        *coordinate = *index as f64;
+                    }
+                    for neighbour in &neighbours {
+                        *value -= neighbour.value(fitter.lineshape, &coordinates);
+                    }
+                    next_indices(&mut point, &ranges);
+                }
+            }
+
+            let peaks: Vec<_> = members.iter().map(|m| parameters[*m].clone()).collect();
+            let (peaks, residual, converged) = fit_group(
+                &values,
+                &ranges,
+                peaks,
+                fitter.lineshape,
+                fitter.max_iterations,
+            );
+
+            for (member, peak) in members.into_iter().zip(peaks) {
+                fitted[member] = Some(FittedPeak {
+                    ppms: peak
+                        .centers
+                        .iter()
+                        .zip(&self.axis_headers)
+                        .map(|(center, axis_header)| axis_header.index_to_ppm(*center))
+                        .collect(),
+                    linewidths_hz: peak
+                        .widths
+                        .iter()
+                        .zip(&self.axis_headers)
+                        .map(|(width, axis_header)| width * axis_header.hz_per_point())
+                        .collect(),
+                    height: peak.height,
+                    volume: peak.height
+                        * peak
+                            .widths
+                            .iter()
+                            .map(|width| fitter.lineshape.integral(*width, peak.eta))
+                            .product::<f64>(),
+                    eta: match fitter.lineshape {
+                        Lineshape::PseudoVoigt => Some(peak.eta),
+                        _ => None,
+                    },
+                    residual,
+                    group,
+                    converged: converged && !peak.at_limit(&ranges),
+                });
+                parameters[member] = peak;
+            }
+        }
+
+        Ok(fitted.into_iter().map(Option::unwrap).collect())
+    }
+}
+
+/// Estimate the parameters of the peak at `indices` from the tiled `data`.
+///
+/// The linewidth along each axis is estimated from where the data first drops below half of the
+/// height.
+fn initial_parameters(data: &[f32], layout: &TileLayout, indices: &[usize]) -> PeakParameters {
+    let height = data[layout.position(indices)] as f64;
+    let widths = (0..indices.len())
+        .map(|axis| {
+            let mut point = indices.to_vec();
+            let mut half_width = |step: isize| {
+                let mut distance = 0;
+                loop {
+                    let next = indices[axis] as isize + step * (distance as isize + 1);
+                    if next < 0 || next as usize >= layout.axis_sizes[axis] {
+                        return distance as f64 + 0.5;
+                    }
+                    point[axis] = next as usize;
+                    let value = data[layout.position(&point)] as f64;
+                    if value.abs() < height.abs() / 2.0 || value.abs() > height.abs() {
+                        return distance as f64 + 0.5;
+                    }
+                    distance += 1;
+                }
+            };
+            (half_width(-1) + half_width(1)).max(1.0)
+        })
+        .collect();
+
+    PeakParameters {
+        height,
+        centers: indices.iter().map(|index| *index as f64).collect(),
+        widths,
+        eta: 0.5,
+    }
+}
+
+/// Whether the boxes given by the ranges `a` and `b` overlap.
+fn overlaps(a: &[Range<usize>], b: &[Range<usize>]) -> bool {
+    a.iter()
+        .zip(b)
+        .all(|(a, b)| a.start < b.end && b.start < a.end)
+}
+
+/// Group the peaks whose neighbourhoods `boxes` overlap (directly or via other peaks), with at
+/// most [`MAX_GROUP_PEAKS`] peaks per group.
+fn overlapping_groups(boxes: &[Vec<Range<usize>>]) -> Vec<Vec<usize>> {
+    let mut group_of: Vec<usize> = (0..boxes.len()).collect();
+    let mut groups: Vec<Vec<usize>> = (0..boxes.len()).map(|peak| vec![peak]).collect();
+    for a in 0..boxes.len() {
+        for b in (a + 1)..boxes.len() {
+            let (group_a, group_b) = (group_of[a], group_of[b]);
+            if group_a == group_b
+                || groups[group_a].len() + groups[group_b].len() > MAX_GROUP_PEAKS
+                || !overlaps(&boxes[a], &boxes[b])
+            {
+                continue;
+            }
+            let moved = std::mem::take(&mut groups[group_b]);
+            for peak in &moved {
+                group_of[*peak] = group_a;
+            }
+            groups[group_a].extend(moved);
+        }
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .map(|mut group| {
+            group.sort_unstable();
+            group
+        })
+        .collect();
+    groups.sort_unstable_by_key(|group| group[0]);
+    groups
+}
+
+/// Fit the `peaks` to the continous `values` within `ranges` with the Levenberg-Marquardt
+/// algorithm.
+///
+/// Returns the fitted peaks, the root mean square of the residuals and whether the optimization
+/// converged within `max_iterations`.
+fn fit_group(
+    values: &[f64],
+    ranges: &[Range<usize>],
+    peaks: Vec<PeakParameters>,
+    lineshape: Lineshape,
+    max_iterations: usize,
+) -> (Vec<PeakParameters>, f64, bool) {
+    let dimensions = ranges.len();
+    let peak_len = PeakParameters::len(dimensions, lineshape);
+
+    // Coordinates of all data points, one after another
+    let mut points = Vec::with_capacity(values.len() * dimensions);
+    let mut indices: Vec<_> = ranges.iter().map(|range| range.start).collect();
+    for _ in values {
+        points.extend(indices.iter().map(|index| *index as f64));
+        next_indices(&mut indices, ranges);
+    }
+
+    let limits = PeakParameters::limits(ranges, lineshape).repeat(peaks.len());
+    let constrain = |params: Vec<f64>| -> Vec<f64> {
+        params
+            .into_iter()
+            .zip(&limits)
+            .map(|(param, (lower, upper))| param.clamp(*lower, *upper))
+            .collect()
+    };
+    let unpack = |params: &[f64]| -> Vec<PeakParameters> {
+        params
+            .chunks(peak_len)
+            .map(|params| PeakParameters::from_slice(params, dimensions, lineshape))
+            .collect()
+    };
+    let residuals = |params: &[f64]| -> Vec<f64> {
+        let peaks = unpack(params);
+        points
+            .chunks(dimensions)
+            .zip(values)
+            .map(|(point, value)| {
+                value
+                    - peaks
+                        .iter()
+                        .map(|peak| peak.value(lineshape, point))
+                        .sum::<f64>()
+            })
+            .collect()
+    };
+    let cost = |residuals: &[f64]| residuals.iter().map(|r| r * r).sum::<f64>();
+
+    let mut params = constrain(
+        peaks
+            .iter()
+            .flat_map(|peak| peak.to_vec(lineshape))
+            .collect(),
+    );
+    let mut current = residuals(&params);
+    let mut current_cost = cost(&current);
+    let mut lambda = 1e-3;
+    let n = params.len();
+    let mut shapes = Vec::with_capacity(dimensions);
+    let mut gradient = vec![0f64; n];
+    let mut converged = false;
+    for _ in 0..max_iterations {
+        // The jacobian of the model (= negative jacobian of the residuals) is accumulated per data
+        // point, so that it never has to be stored as a whole
+        let peaks = unpack(&params);
+        let mut jtj = vec![vec![0f64; n]; n];
+        let mut jtr = vec![0f64; n];
+        for (point, residual) in points.chunks(dimensions).zip(&current) {
+            for (peak, gradient) in peaks.iter().zip(gradient.chunks_mut(peak_len)) {
+                peak.value_and_gradient(lineshape, point, &mut shapes, gradient);
+            }
+            for (a, (row, jtr)) in jtj.iter_mut().zip(jtr.iter_mut()).enumerate() {
+                let gradient_a = gradient[a];
+                if gradient_a == 0.0 {
+                    continue;
+                }
+                for (entry, gradient_b) in row[a..].iter_mut().zip(&gradient[a..]) {
+                    *entry += gradient_a * gradient_b;
+                }
+                *jtr += gradient_a * residual;
+            }
+        }
+        for a in 1..n {
+            let (upper, lower) = jtj.split_at_mut(a);
+            for (entry, row) in lower[0].iter_mut().zip(upper.iter()) {
+                *entry = row[a];
+            }
+        }
+
+        // Parameters at their limit, which would be pushed beyond it, are kept fixed
+        let fixed: Vec<_> = params
+            .iter()
+            .zip(&limits)
+            .zip(&jtr)
+            .map(|((param, (lower, upper)), descent)| {
+                (*param <= *lower && *descent < 0.0) || (*param >= *upper && *descent > 0.0)
+            })
+            .collect();
+        for (a, fixed) in fixed.iter().enumerate() {
+            if *fixed {
+                jtj[a].iter_mut().for_each(|entry| *entry = 0.0);
+                for row in jtj.iter_mut() {
+                    row[a] = 0.0;
+                }
+                jtr[a] = 0.0;
+            }
+        }
+
+        let mut improved = false;
+        while lambda < 1e10 {
+            let mut damped = jtj.clone();
+            for (a, row) in damped.iter_mut().enumerate() {
+                row[a] += lambda * jtj[a][a].max(1e-12);
+            }
+            if let Some(delta) = solve(damped, jtr.clone()) {
+                let candidate = constrain(params.iter().zip(&delta).map(|(p, d)| p + d).collect());
+                let candidate_residuals = residuals(&candidate);
+                let candidate_cost = cost(&candidate_residuals);
+                if candidate_cost < current_cost {
+                    let small_step = (current_cost - candidate_cost) <= 1e-6 * current_cost;
+                    params = candidate;
+                    current = candidate_residuals;
+                    current_cost = candidate_cost;
+                    lambda = (lambda / 10.0).max(1e-12);
+                    improved = !small_step;
+                    break;
+                }
+            }
+            lambda *= 10.0;
+        }
+        if !improved {
+            converged = true;
+            break;
+        }
+    }
+
+    let rms = (current_cost / values.len() as f64).sqrt();
+    (unpack(&params), rms, converged)
+}
+
+/// Solve the linear system `matrix * x = vector` via gaussian elimination with partial pivoting.
+///
+/// Returns `None` if the matrix is singular.
+fn solve(mut matrix: Vec<Vec<f64>>, mut vector: Vec<f64>) -> Option<Vec<f64>> {
+    let n = vector.len();
+    for column in 0..n {
+        let pivot = (column..n).max_by(|a, b| {
+            matrix[*a][column]
+                .abs()
+                .total_cmp(&matrix[*b][column].abs())
+        })?;
+        if matrix[pivot][column].abs() < f64::MIN_POSITIVE {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        vector.swap(column, pivot);
+
+        let (pivot_rows, rows) = matrix.split_at_mut(column + 1);
+        let (pivot_values, values) = vector.split_at_mut(column + 1);
+        let pivot_row = &pivot_rows[column];
+        for (row, value) in rows.iter_mut().zip(values) {
+            let factor = row[column] / pivot_row[column];
+            for (entry, pivot_entry) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *entry -= factor * pivot_entry;
+            }
+            *value -= factor * pivot_values[column];
+        }
+    }
+
+    let mut solution = vec![0f64; n];
+    for row in (0..n).rev() {
+        let sum: f64 = ((row + 1)..n).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (vector[row] - sum) / matrix[row][row];
+    }
+    Some(solution)
+}
@@ -44,23 +44,99 @@ use nom::number::complete::{be_f32, be_u16, be_u32, be_u8};
 use nom::sequence::tuple;
 use nom::IResult;
 use std::convert::TryInto;
+use std::io::{self, Write};
+use std::ops::Range;
 use thiserror::Error;
 
+mod arithmetic;
+#[cfg(feature = "ndarray")]
+mod array;
+mod builder;
+mod contour;
+mod fitting;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod peaks;
+mod projection;
+mod reader;
+mod region;
+#[cfg(feature = "render")]
+mod render;
+mod resample;
+mod slice;
+mod spectrum;
+mod statistics;
+mod view;
+
+pub use builder::UcsfFileBuilder;
+pub use contour::{AutoContourLevels, Contour, ContourLevels};
+pub use fitting::{FittedPeak, Lineshape, PeakFitter};
+pub use peaks::{Peak, PeakPicker, Separation, Threshold};
+pub use projection::{AxisProjection, ProjectionMode};
+pub use reader::{OwnedTile, ReaderTiles, UcsfReader};
+pub use region::AxisRegion;
+#[cfg(feature = "render")]
+pub use render::{Colormap, RenderOptions, RenderStyle};
+pub use resample::{Interpolation, OutsideValue};
+pub use slice::{AxisPosition, Trace};
+pub use spectrum::{Spectrum, SpectrumPosValIter, SpectrumTile, SpectrumTiles};
+pub use statistics::{Histogram, Statistics};
+pub use view::{UcsfView, ViewAbsolutePosValIter, ViewTile, ViewTiles};
+
 #[derive(Error, Debug, PartialEq)]
 pub enum UcsfError {
     #[error("Unsupported format version. Currently the parser only supports format version 2.")]
     UnsupportedFormat,
-    #[error("Unsupported number of components. Currently the parser only supports files with a number of1 components per data point (= Real).")]
+    #[error("Unsupported number of components. Currently the parser only supports files with 1 (= Real) or 2 (= Complex) components per data point.")]
     UnsupportedComponents,
     #[error("Failed to parse")]
     Parsing,
+    #[error("Failed to read: {0:?}")]
+    Io(io::ErrorKind),
+    #[error("Tile index out of range")]
+    TileOutOfRange,
+    #[error("Expected {expected} axes, but got {actual}")]
+    DimensionMismatch { expected: usize, actual: usize },
+    #[error("Region along axis {0} contains no data points")]
+    EmptyRegion(usize),
+    #[error("Axis {0} does not exist")]
+    InvalidAxis(usize),
+    #[error("Position is outside of the spectrum")]
+    OutOfBounds,
+    #[error("Expected {expected} free axes, but got {actual}")]
+    FreeAxesMismatch { expected: usize, actual: usize },
+    #[error("At least one axis has to be kept")]
+    NoAxesKept,
+    #[error("A spectrum has to have at least one axis")]
+    NoAxes,
+    #[error("Expected {expected} data points, but got {actual}")]
+    DataSizeMismatch { expected: usize, actual: usize },
+    #[error("Axis {0} has an invalid size")]
+    InvalidAxisSize(usize),
+    #[error("Axis {axis} doesn't match, as its {property} differs")]
+    IncompatibleAxis { axis: usize, property: &'static str },
+    #[error("At least one spectrum is required")]
+    NoSpectra,
+    #[error("Failed to render: {0}")]
+    Render(String),
+}
+
+impl From<io::Error> for UcsfError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err.kind())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct UcsfFile {
     pub header: Header,
     pub axis_headers: Vec<AxisHeader>,
+    /// Real part of the data in its native tile-layout.
     pub data: Vec<f32>,
+    /// Imaginary part of the data in its native tile-layout.
+    ///
+    /// Only present for files with 2 components per data point (= Complex).
+    pub imaginary_data: Option<Vec<f32>>,
 }
 
 impl UcsfFile {
@@ -77,40 +153,180 @@ impl UcsfFile {
         take(size)(input)
     }
 
-    pub fn parse(input: &[u8]) -> Result<(&[u8], Self), UcsfError> {
-        let (mut rem, header) = Header::parse(&input)?;
+    /// Parse the header and axis headers, and take the raw bytes of the data.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn parse_raw(
+        input: &[u8],
+    ) -> Result<(&[u8], Header, Vec<AxisHeader>, &[u8]), UcsfError> {
+        let (mut rem, header) = Header::parse(input)?;
         let mut axis_headers = vec![];
         for _ in 0..header.dimensions {
-            let (_rem, axis_header) = AxisHeader::parse(&rem)?;
+            let (_rem, axis_header) = AxisHeader::parse(rem)?;
             rem = _rem;
             axis_headers.push(axis_header);
         }
 
-        let data_size = Self::calculate_data_size(&axis_headers);
+        let components = header.components as usize;
+        let data_size = Self::calculate_data_size(&axis_headers) * components;
         let (rem, data) = Self::parse_data_raw(rem, data_size).map_err(|_| UcsfError::Parsing)?;
-        let float_data: Vec<f32> = data
-            .chunks(4)
-            .map(|chunk| f32::from_be_bytes(chunk.try_into().unwrap()))
-            .collect();
+
+        Ok((rem, header, axis_headers, data))
+    }
+
+    pub fn parse(input: &[u8]) -> Result<(&[u8], Self), UcsfError> {
+        let (rem, header, axis_headers, data) = Self::parse_raw(input)?;
+        let (data, imaginary_data) = decode_data(data, header.components);
 
         Ok((
             rem,
             Self {
                 header,
                 axis_headers,
-                data: float_data,
+                data,
+                imaginary_data,
             },
         ))
     }
 
+    /// Serialize the file into the UCSF format.
+    ///
+    /// Parsing the returned bytes with [`UcsfFile::parse`] yields the same headers and data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            180 + 128 * self.axis_headers.len()
+                + Self::calculate_data_size(&self.axis_headers) * self.header.components as usize,
+        );
+        self.write(&mut bytes)
+            .expect("Writing to a Vec should never fail");
+        bytes
+    }
+
+    /// Write the file in the UCSF format to `writer`.
+    ///
+    /// The data is expected to be in its native tile-layout (the same layout as [`UcsfFile::data`]).
+    /// If the header specifies 2 components, the real and imaginary values are written interleaved,
+    /// with missing imaginary data written as zeros.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.header.to_bytes())?;
+        for axis_header in &self.axis_headers {
+            writer.write_all(&axis_header.to_bytes())?;
+        }
+
+        // Data is written tile by tile, so only the bytes of a single tile are buffered
+        let components = self.header.components as usize;
+        let tile_data_points = self.axis_tile_sizes().iter().product::<usize>().max(1);
+        let mut bytes = Vec::with_capacity(tile_data_points * components * 4);
+        for (tile_n, tile) in self.data.chunks(tile_data_points).enumerate() {
+            bytes.clear();
+            for (i, real) in tile.iter().enumerate() {
+                bytes.extend_from_slice(&real.to_be_bytes());
+                if components == 2 {
+                    let imaginary = self
+                        .imaginary_data
+                        .as_ref()
+                        .map(|imaginary_data| imaginary_data[tile_n * tile_data_points + i])
+                        .unwrap_or(0.0);
+                    bytes.extend_from_slice(&imaginary.to_be_bytes());
+                }
+            }
+            writer.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
     /// Returns the amount of data points along `axis`.
     pub fn axis_data_points(&self, axis: usize) -> u32 {
         self.axis_headers[axis].data_points
     }
 
+    /// Check that `point` is a valid multi-dimension-index into the spectrum.
+    fn check_point(&self, point: &[usize]) -> Result<(), UcsfError> {
+        if point.len() != self.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: point.len(),
+            });
+        }
+        let in_bounds = point
+            .iter()
+            .zip(&self.axis_headers)
+            .all(|(index, axis)| *index < axis.data_points as usize);
+        match in_bounds {
+            true => Ok(()),
+            false => Err(UcsfError::OutOfBounds),
+        }
+    }
+
+    /// Returns the value of the data point at the multi-dimension-index `indices`.
+    ///
+    /// Returns `None` if the amount of indices doesn't match the dimensions of the spectrum, or
+    /// an index is out of bounds.
+    pub fn get(&self, indices: &[usize]) -> Option<f32> {
+        self.check_point(indices).ok()?;
+
+        Some(self.data[tiled_position(&self.axis_headers, indices)])
+    }
+
+    /// Returns the value of the data point closest to the ppm position `ppms`.
+    ///
+    /// Returns `None` if the amount of ppm values doesn't match the dimensions of the spectrum,
+    /// or the position lies outside of the spectrum.
+    pub fn get_ppm(&self, ppms: &[f64]) -> Option<f32> {
+        self.get(&self.ppm_to_indices(ppms)?)
+    }
+
+    /// Convert a ppm position to the multi-dimension-index of the closest data point.
+    ///
+    /// Returns `None` if the amount of ppm values doesn't match the dimensions of the spectrum,
+    /// or the position lies outside of the spectrum.
+    pub fn ppm_to_indices(&self, ppms: &[f64]) -> Option<Vec<usize>> {
+        if ppms.len() != self.axis_headers.len() {
+            return None;
+        }
+        ppms.iter()
+            .zip(&self.axis_headers)
+            .map(|(ppm, axis)| {
+                let index = axis.ppm_to_index(*ppm).round();
+                match index >= 0.0 && index < axis.data_points as f64 {
+                    true => Some(index as usize),
+                    false => None,
+                }
+            })
+            .collect()
+    }
+
     /// Returns an iterator over all tiles in the file.
     pub fn tiles(&self) -> Tiles<'_> {
-        Tiles::for_file(&self)
+        Tiles::for_file(self)
+    }
+
+    /// Returns the tile with index `tile_n`.
+    ///
+    /// Tiles are numbered in the same order as they are returned by [`UcsfFile::tiles`].
+    pub fn tile(&self, tile_n: usize) -> Option<Tile<'_>> {
+        let layout = TileLayout::new(&self.axis_headers);
+        if layout.num_tiles() <= tile_n {
+            return None;
+        }
+
+        let geometry = layout.geometry(&multi_dim_index(&layout.axis_tiles, tile_n));
+        Some(self.tile_with_geometry(tile_n, geometry))
+    }
+
+    fn tile_with_geometry(&self, tile_n: usize, geometry: TileGeometry) -> Tile<'_> {
+        let data_range_start = geometry.data_points() * tile_n;
+        let data_range_end = data_range_start + geometry.data_points();
+
+        Tile {
+            axis_lengths: geometry.axis_lengths,
+            axis_starts: geometry.axis_starts,
+            padded_axis_lengths: geometry.padded_axis_lengths,
+            data: &self.data[data_range_start..data_range_end],
+            imaginary_data: self
+                .imaginary_data
+                .as_ref()
+                .map(|imaginary_data| &imaginary_data[data_range_start..data_range_end]),
+        }
     }
 
     /// Returns the amount of tiles along each axis.
@@ -145,12 +361,29 @@ impl UcsfFile {
     /// This provides an alternative way to accessing the data in its native
     /// tile-layout.
     pub fn data_continous(&self) -> Vec<f32> {
-        let total_size = Self::calculate_data_size(&self.axis_headers);
+        self.to_continous(false)
+    }
+
+    /// Construct a Vec where the imaginary data is layed out continously per-axis.
+    ///
+    /// Returns `None` if the file contains no imaginary data.
+    pub fn imaginary_data_continous(&self) -> Option<Vec<f32>> {
+        self.imaginary_data.as_ref()?;
+        Some(self.to_continous(true))
+    }
+
+    fn to_continous(&self, imaginary: bool) -> Vec<f32> {
+        let axis_sizes = self.axis_sizes();
+        let total_size = axis_sizes.iter().product();
         let mut data = [0f32].repeat(total_size);
 
         for tile in self.tiles() {
-            for (axis_indices, value) in tile.iter_with_abolute_pos() {
-                let pos = multi_dim_position(&self.axis_sizes(), &axis_indices);
+            let mut values = match imaginary {
+                false => tile.iter_with_abolute_pos(),
+                true => tile.iter_imaginary_with_abolute_pos().unwrap(),
+            };
+            while let Some((axis_indices, value)) = values.next_with_pos() {
+                let pos = multi_dim_position(&axis_sizes, axis_indices);
                 data[pos] = value;
             }
         }
@@ -158,13 +391,14 @@ impl UcsfFile {
     }
 
     /// Returns the lower and upper bounds of the data.
+    ///
+    /// Zero-padding and values that are not finite are ignored. Returns `(NaN, NaN)` if the
+    /// spectrum contains no finite values. See [`UcsfFile::statistics`] for more statistics.
     pub fn bounds(&self) -> (f32, f32) {
-        let mut sorted_data = self.data.to_vec();
-        sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let min_val: f32 = *sorted_data.first().unwrap();
-        let max_val: f32 = *sorted_data.last().unwrap();
-
-        (min_val, max_val)
+        match self.statistics() {
+            Some(statistics) => (statistics.min, statistics.max),
+            None => (f32::NAN, f32::NAN),
+        }
     }
 }
 
@@ -187,7 +421,7 @@ pub struct Header {
     /// Number of components for each data point.
     ///
     /// 1 = Real
-    /// 2 = Imaginary
+    /// 2 = Complex (real and imaginary part)
     ///
     /// ### Format
     /// Bytes: 11-11
@@ -209,6 +443,7 @@ pub struct Header {
 }
 
 impl Header {
+    #[allow(clippy::type_complexity)]
     fn parse_raw(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8], u8, u8, u16, &[u8])> {
         tuple((
             tag(b"UCSF NMR"),
@@ -231,7 +466,7 @@ impl Header {
             format_version,
             remainder,
         ): (_, _, _, _, _, &[u8])| {
-            if components != 1 {
+            if components != 1 && components != 2 {
                 return Err(UcsfError::UnsupportedComponents);
             }
             if format_version != 2 {
@@ -251,6 +486,20 @@ impl Header {
 
         map(res)
     }
+
+    /// Serialize the header into its 180 byte representation.
+    ///
+    /// `remainder` is zero-padded or truncated to fit into the available 166 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(180);
+        bytes.extend_from_slice(b"UCSF NMR");
+        bytes.extend_from_slice(&[0u8; 2]);
+        bytes.push(self.dimensions);
+        bytes.push(self.components);
+        bytes.extend_from_slice(&self.format_version.to_be_bytes());
+        bytes.extend(padded_bytes(&self.remainder, 166));
+        bytes
+    }
 }
 
 /// 128 byte axis header
@@ -264,8 +513,13 @@ pub struct AxisHeader {
     /// Number of data points along this axis.
     ///
     /// ### Format
-    /// Bytes 8-11 (followed by 4 unknown bytes)
+    /// Bytes 8-11
     pub data_points: u32,
+    /// Unknown bytes, in which Sparky writes a copy of the number of data points.
+    ///
+    /// ### Format
+    /// Bytes 12-15
+    pub unknown: [u8; 4],
     /// Tile size along this axis.
     ///
     /// ### Format
@@ -294,6 +548,27 @@ pub struct AxisHeader {
 }
 
 impl AxisHeader {
+    /// Create an axis header with the tile size set to the amount of data points.
+    pub fn new(
+        nucleus_name: &str,
+        data_points: u32,
+        frequency: f32,
+        spectral_width: f32,
+        center: f32,
+    ) -> Self {
+        Self {
+            nucleus_name: nucleus_name.to_owned(),
+            data_points,
+            unknown: data_points.to_be_bytes(),
+            tile_size: data_points,
+            frequency,
+            spectral_width,
+            center,
+            remainder: [0u8].repeat(96),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
     fn parse_raw(input: &[u8]) -> IResult<&[u8], (&[u8], u32, &[u8], u32, f32, f32, f32, &[u8])> {
         tuple((
             take(8u8),
@@ -313,13 +588,13 @@ impl AxisHeader {
         let map = |(
             nucleus_name,
             data_points,
-            _unknown,
+            unknown,
             tile_size,
             frequency,
             spectral_width,
             center,
             remainder,
-        ): (&[u8], _, _, _, _, _, _, &[u8])| {
+        ): (&[u8], _, &[u8], _, _, _, _, &[u8])| {
             let nucleus_name =
                 String::from_utf8_lossy(nucleus_name.split(|n| *n == 0u8).next().unwrap())
                     .trim_end()
@@ -329,6 +604,7 @@ impl AxisHeader {
                 Self {
                     nucleus_name,
                     data_points,
+                    unknown: unknown.try_into().unwrap(),
                     tile_size,
                     frequency,
                     spectral_width,
@@ -343,9 +619,8 @@ impl AxisHeader {
 
     /// Returns the amount of tiles along this axis.
     pub fn num_tiles(&self) -> u32 {
-        // We are adding `self.tile_size - 1`, to ensure we always round up
-        // for zero-padded tiles
-        (self.data_points + self.tile_size - 1) / self.tile_size
+        // Always round up for zero-padded tiles
+        self.data_points.div_ceil(self.tile_size)
     }
 
     /// Returns the size of the axis including zero-padding.
@@ -369,6 +644,106 @@ impl AxisHeader {
             true => self.padded_size() - self.data_points,
         }
     }
+
+    /// Returns the spectral width in ppm.
+    pub fn spectral_width_ppm(&self) -> f64 {
+        self.spectral_width as f64 / self.frequency as f64
+    }
+
+    /// Returns the distance between two neighbouring data points in ppm.
+    pub fn ppm_per_point(&self) -> f64 {
+        self.spectral_width_ppm() / self.data_points as f64
+    }
+
+    /// Returns the distance between two neighbouring data points in Hz.
+    pub fn hz_per_point(&self) -> f64 {
+        self.spectral_width as f64 / self.data_points as f64
+    }
+
+    /// Convert a (fractional) data point index along this axis to ppm.
+    ///
+    /// Follows the Sparky convention: The data point with index `data_points / 2` lies at
+    /// [`center`](#structfield.center), and the ppm values decrease with increasing index.
+    pub fn index_to_ppm(&self, index: f64) -> f64 {
+        self.center as f64 + (self.data_points as f64 / 2.0 - index) * self.ppm_per_point()
+    }
+
+    /// Convert a ppm value to a (fractional) data point index along this axis.
+    ///
+    /// Inverse of [`AxisHeader::index_to_ppm`].
+    pub fn ppm_to_index(&self, ppm: f64) -> f64 {
+        self.data_points as f64 / 2.0 - (ppm - self.center as f64) / self.ppm_per_point()
+    }
+
+    /// Convert a (fractional) data point index along this axis to Hz.
+    ///
+    /// The Hz scale has its origin at 0 ppm, like in Sparky.
+    pub fn index_to_hz(&self, index: f64) -> f64 {
+        self.index_to_ppm(index) * self.frequency as f64
+    }
+
+    /// Convert a Hz value to a (fractional) data point index along this axis.
+    ///
+    /// Inverse of [`AxisHeader::index_to_hz`].
+    pub fn hz_to_index(&self, hz: f64) -> f64 {
+        self.ppm_to_index(hz / self.frequency as f64)
+    }
+
+    /// Returns the lowest and highest ppm value of the data points along this axis.
+    pub fn ppm_range(&self) -> (f64, f64) {
+        (
+            self.index_to_ppm((self.data_points - 1) as f64),
+            self.index_to_ppm(0.0),
+        )
+    }
+
+    /// Returns the ppm value of every data point along this axis.
+    pub fn ppm_scale(&self) -> Vec<f64> {
+        (0..self.data_points)
+            .map(|index| self.index_to_ppm(index as f64))
+            .collect()
+    }
+
+    /// Returns the range of data point indices whose ppm values lie between `ppm_a` and `ppm_b`
+    /// (inclusive, in any order).
+    ///
+    /// The returned range is clamped to the data points along this axis, and may be empty.
+    pub fn ppm_to_index_range(&self, ppm_a: f64, ppm_b: f64) -> Range<usize> {
+        // Small tolerance, so that ppm values calculated from an index map back onto that index
+        const TOLERANCE: f64 = 1e-6;
+        let index_a = self.ppm_to_index(ppm_a.max(ppm_b));
+        let index_b = self.ppm_to_index(ppm_a.min(ppm_b));
+
+        let start = (index_a - TOLERANCE).ceil().max(0.0) as usize;
+        let end = ((index_b + TOLERANCE).floor() + 1.0).max(0.0) as usize;
+        let end = end.min(self.data_points as usize);
+        start.min(end)..end
+    }
+
+    /// Serialize the axis header into its 128 byte representation.
+    ///
+    /// `nucleus_name` and `remainder` are zero-padded or truncated to fit into the available space.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(128);
+        bytes.extend(padded_bytes(self.nucleus_name.as_bytes(), 8));
+        bytes.extend_from_slice(&self.data_points.to_be_bytes());
+        bytes.extend_from_slice(&self.unknown);
+        bytes.extend_from_slice(&self.tile_size.to_be_bytes());
+        bytes.extend_from_slice(&self.frequency.to_be_bytes());
+        bytes.extend_from_slice(&self.spectral_width.to_be_bytes());
+        bytes.extend_from_slice(&self.center.to_be_bytes());
+        bytes.extend(padded_bytes(&self.remainder, 96));
+        bytes
+    }
+}
+
+/// Zero-pad or truncate `bytes` to exactly `len` bytes.
+fn padded_bytes(bytes: &[u8], len: usize) -> impl Iterator<Item = u8> + '_ {
+    bytes
+        .iter()
+        .copied()
+        .chain(std::iter::repeat(0u8))
+        .take(len)
 }
 
 pub struct Tile<'a> {
@@ -380,66 +755,178 @@ pub struct Tile<'a> {
     // pub axis_2_start: usize,
     /// Index of first element of axis 2 (in relation to total axis).
     pub axis_starts: Vec<usize>,
-    /// View into underlying data
+    /// Amount of data points along each axis in this tile, including zero-padding.
+    ///
+    /// This is the tile size of the file and the layout of [`Tile::data`].
+    pub padded_axis_lengths: Vec<usize>,
+    /// View into underlying data (including zero-padding)
     pub data: &'a [f32],
+    /// View into underlying imaginary data (including zero-padding)
+    ///
+    /// Only present for files with complex data.
+    pub imaginary_data: Option<&'a [f32]>,
 }
 
 impl<'a> Tile<'a> {
+    /// Returns the raw data of the tile in its native layout, including zero-padding.
     pub fn data(&self) -> &[f32] {
-        &self.data
+        self.data
     }
 
     /// Iterate over the values in a tile with their absolute position in the spectrum.
     ///
     /// **No specific order of the values should be assumes, which is why the position is provided
     /// in the iterator**
-    pub fn iter_with_abolute_pos(&self) -> AbsolutePosValIter<'_> {
-        AbsolutePosValIter {
-            tile: self,
-            next_index: 0,
+    pub fn iter_with_abolute_pos(&self) -> AbsolutePosValIter<'a> {
+        AbsolutePosValIter::new(self, self.data)
+    }
+
+    /// Returns the raw imaginary data of the tile in its native layout, including zero-padding.
+    pub fn imaginary_data(&self) -> Option<&[f32]> {
+        self.imaginary_data
+    }
+
+    /// Iterate over the imaginary values in a tile with their absolute position in the spectrum.
+    ///
+    /// Returns `None` if the file contains no imaginary data.
+    pub fn iter_imaginary_with_abolute_pos(&self) -> Option<AbsolutePosValIter<'a>> {
+        Some(AbsolutePosValIter::new(self, self.imaginary_data?))
+    }
+}
+
+/// Maximum amount of axes, for which an [`AbsolutePos`] is stored without heap allocation.
+const INLINE_AXES: usize = 8;
+
+/// Absolute position of a data point in the spectrum, with one index per axis.
+///
+/// Dereferences to a slice of the indices. Positions in spectra with up to 8 axes are stored
+/// inline, so that iterating over data points with their positions needs no heap allocations.
+#[derive(Debug, Clone)]
+pub struct AbsolutePos(AbsolutePosIndices);
+
+#[derive(Debug, Clone)]
+enum AbsolutePosIndices {
+    Inline {
+        len: usize,
+        indices: [usize; INLINE_AXES],
+    },
+    Heap(Vec<usize>),
+}
+
+impl AbsolutePos {
+    #[inline]
+    fn from_slice(position: &[usize]) -> Self {
+        match position.len() <= INLINE_AXES {
+            true => {
+                let mut indices = [0; INLINE_AXES];
+                indices[..position.len()].copy_from_slice(position);
+                Self(AbsolutePosIndices::Inline {
+                    len: position.len(),
+                    indices,
+                })
+            }
+            false => Self(AbsolutePosIndices::Heap(position.to_vec())),
+        }
+    }
+}
+
+impl std::ops::Deref for AbsolutePos {
+    type Target = [usize];
+
+    #[inline]
+    fn deref(&self) -> &[usize] {
+        match &self.0 {
+            AbsolutePosIndices::Inline { len, indices } => &indices[..*len],
+            AbsolutePosIndices::Heap(indices) => indices,
         }
     }
 }
 
+impl PartialEq for AbsolutePos {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Eq for AbsolutePos {}
+
+impl PartialEq<[usize]> for AbsolutePos {
+    fn eq(&self, other: &[usize]) -> bool {
+        self[..] == *other
+    }
+}
+
+impl PartialEq<Vec<usize>> for AbsolutePos {
+    fn eq(&self, other: &Vec<usize>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl From<AbsolutePos> for Vec<usize> {
+    fn from(position: AbsolutePos) -> Self {
+        position.to_vec()
+    }
+}
+
 pub struct AbsolutePosValIter<'a> {
-    tile: &'a Tile<'a>,
-    next_index: usize,
+    positions: TilePositions,
+    data: &'a [f32],
 }
 
 impl<'a> AbsolutePosValIter<'a> {
+    fn new(tile: &Tile<'_>, data: &'a [f32]) -> Self {
+        Self {
+            positions: TilePositions::new(
+                &tile.axis_lengths,
+                &tile.axis_starts,
+                &tile.padded_axis_lengths,
+            ),
+            data,
+        }
+    }
+
+    /// Advance the iterator and return the absolute position and value of the next data point.
+    ///
+    /// In contrast to [`Iterator::next`], the position is borrowed from the iterator, so no
+    /// allocation is necessary.
+    #[inline]
+    pub fn next_with_pos(&mut self) -> Option<(&[usize], f32)> {
+        let index = self.positions.advance()?;
+        Some((self.positions.position(), self.data[index]))
+    }
+
+    /// View the iterator as one over 2D positions.
+    ///
+    /// Panics if the spectrum has less than 2 axes. See [`Spectrum`] for positions whose
+    /// dimensionality is checked when converting the file.
     pub fn as_2d(&'a mut self) -> AbsolutePosValIter2D<'a> {
         AbsolutePosValIter2D { iter: self }
     }
 
+    /// View the iterator as one over 3D positions.
+    ///
+    /// Panics if the spectrum has less than 3 axes. See [`Spectrum`] for positions whose
+    /// dimensionality is checked when converting the file.
     pub fn as_3d(&'a mut self) -> AbsolutePosValIter3D<'a> {
         AbsolutePosValIter3D { iter: self }
     }
 
+    /// View the iterator as one over 4D positions.
+    ///
+    /// Panics if the spectrum has less than 4 axes. See [`Spectrum`] for positions whose
+    /// dimensionality is checked when converting the file.
     pub fn as_4d(&'a mut self) -> AbsolutePosValIter4D<'a> {
         AbsolutePosValIter4D { iter: self }
     }
 }
 
 impl<'a> Iterator for AbsolutePosValIter<'a> {
-    type Item = (Vec<usize>, f32);
+    type Item = (AbsolutePos, f32);
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.next_index >= self.tile.data().len() {
-            return None;
-        }
-
-        // Position relative to the current tile
-        let axis_rel = multi_dim_index(&self.tile.axis_lengths, self.next_index);
-        // Absolute position
-        let axis_abs: Vec<_> = axis_rel
-            .iter()
-            .zip(&self.tile.axis_starts)
-            .map(|(axis_relative, axis_start)| axis_relative + axis_start)
-            .collect();
-
-        let val = self.tile.data()[self.next_index];
-        self.next_index += 1;
-        Some(((axis_abs), val))
+        self.next_with_pos()
+            .map(|(axis_abs, val)| (AbsolutePos::from_slice(axis_abs), val))
     }
 }
 
@@ -450,9 +937,10 @@ pub struct AbsolutePosValIter2D<'a> {
 impl<'a> Iterator for AbsolutePosValIter2D<'a> {
     type Item = ((usize, usize), f32);
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.iter
-            .next()
+            .next_with_pos()
             .map(|(axis_abs, val)| ((axis_abs[0], axis_abs[1]), val))
     }
 }
@@ -464,9 +952,10 @@ pub struct AbsolutePosValIter3D<'a> {
 impl<'a> Iterator for AbsolutePosValIter3D<'a> {
     type Item = ((usize, usize, usize), f32);
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.iter
-            .next()
+            .next_with_pos()
             .map(|(axis_abs, val)| ((axis_abs[0], axis_abs[1], axis_abs[2]), val))
     }
 }
@@ -478,22 +967,32 @@ pub struct AbsolutePosValIter4D<'a> {
 impl<'a> Iterator for AbsolutePosValIter4D<'a> {
     type Item = ((usize, usize, usize, usize), f32);
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.iter
-            .next()
+            .next_with_pos()
             .map(|(axis_abs, val)| ((axis_abs[0], axis_abs[1], axis_abs[2], axis_abs[3]), val))
     }
 }
 
 pub struct Tiles<'a> {
     next_index: usize,
+    /// Index of the next tile along each axis.
+    tile_indices: Vec<usize>,
+    /// Layout of the tiles, which is the same for all tiles.
+    layout: TileLayout,
+    num_tiles: usize,
     file: &'a UcsfFile,
 }
 
 impl<'a> Tiles<'a> {
     pub fn for_file(file: &'a UcsfFile) -> Self {
+        let layout = TileLayout::new(&file.axis_headers);
         Self {
             next_index: 0,
+            tile_indices: [0usize].repeat(layout.axis_tiles.len()),
+            num_tiles: layout.num_tiles(),
+            layout,
             file,
         }
     }
@@ -503,52 +1002,281 @@ impl<'a> Iterator for Tiles<'a> {
     type Item = Tile<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let tiles_per_axis = self.file.axis_tiles();
-        let tiles_total: usize = tiles_per_axis.iter().product();
-        if tiles_total <= self.next_index {
+        if self.next_index >= self.num_tiles {
             return None;
         }
 
-        let tile_indices = multi_dim_index(&tiles_per_axis, self.next_index);
+        let geometry = self.layout.geometry(&self.tile_indices);
+        let tile = self.file.tile_with_geometry(self.next_index, geometry);
+        self.next_index += 1;
+        // Increment like an odometer, starting at the last axis
+        for (tile_index, tiles) in self
+            .tile_indices
+            .iter_mut()
+            .zip(&self.layout.axis_tiles)
+            .rev()
+        {
+            *tile_index += 1;
+            if *tile_index < *tiles {
+                break;
+            }
+            *tile_index = 0;
+        }
+        Some(tile)
+    }
+}
+
+/// Location and size of a single tile in the spectrum.
+struct TileGeometry {
+    /// Amount of data points along each axis in this tile (without padding).
+    axis_lengths: Vec<usize>,
+    /// Index of first element along each axis (in relation to total axis).
+    axis_starts: Vec<usize>,
+    /// Amount of data points along each axis in this tile, including zero-padding.
+    padded_axis_lengths: Vec<usize>,
+}
 
-        // Size of a normal (unpadded) tile
-        let axis_tile_sizes = self.file.axis_tile_sizes();
-        // Size of this tile (without padding)
-        let this_tile_axis_lens: Vec<_> = axis_tile_sizes
+impl TileGeometry {
+    fn for_tile(axis_headers: &[AxisHeader], tile_n: usize) -> Self {
+        let layout = TileLayout::new(axis_headers);
+        layout.geometry(&multi_dim_index(&layout.axis_tiles, tile_n))
+    }
+
+    /// Amount of data points stored for the tile.
+    ///
+    /// All tiles are stored with their full size, including zero-padding.
+    fn data_points(&self) -> usize {
+        self.padded_axis_lengths.iter().product()
+    }
+}
+
+/// Walks over the data points of a tile (without padding) in their native order, while keeping
+/// track of their absolute position in the spectrum.
+///
+/// The position is updated incrementally, so no allocations happen per data point.
+struct TilePositions {
+    /// Amount of data points along each axis in the tile (without padding).
+    axis_lengths: Vec<usize>,
+    /// Distance in the tile data between neighbouring data points along each axis.
+    strides: Vec<usize>,
+    /// Position of the current data point relative to the tile.
+    axis_rel: Vec<usize>,
+    /// Absolute position of the current data point.
+    axis_abs: Vec<usize>,
+    /// Index of the current data point in the tile data.
+    index: usize,
+    started: bool,
+}
+
+impl TilePositions {
+    fn new(axis_lengths: &[usize], axis_starts: &[usize], padded_axis_lengths: &[usize]) -> Self {
+        let mut strides = [1usize].repeat(padded_axis_lengths.len());
+        for dim in (0..padded_axis_lengths.len().saturating_sub(1)).rev() {
+            strides[dim] = strides[dim + 1] * padded_axis_lengths[dim + 1];
+        }
+
+        Self {
+            axis_lengths: axis_lengths.to_vec(),
+            strides,
+            axis_rel: [0usize].repeat(axis_lengths.len()),
+            axis_abs: axis_starts.to_vec(),
+            index: 0,
+            started: false,
+        }
+    }
+
+    /// Advance to the next data point and return its index in the tile data.
+    #[inline]
+    fn advance(&mut self) -> Option<usize> {
+        if !self.started {
+            self.started = true;
+            return match self.axis_lengths.iter().all(|length| *length > 0) {
+                true => Some(self.index),
+                false => None,
+            };
+        }
+
+        // Increment like an odometer, starting at the last axis
+        for dim in (0..self.axis_lengths.len()).rev() {
+            self.axis_rel[dim] += 1;
+            self.axis_abs[dim] += 1;
+            self.index += self.strides[dim];
+            if self.axis_rel[dim] < self.axis_lengths[dim] {
+                return Some(self.index);
+            }
+
+            self.index -= self.axis_rel[dim] * self.strides[dim];
+            self.axis_abs[dim] -= self.axis_rel[dim];
+            self.axis_rel[dim] = 0;
+        }
+
+        // Wrapped around on all axes, so all data points have been visited. Keep the state
+        // wrapped, so that further calls also return `None`.
+        self.axis_lengths.iter_mut().for_each(|length| *length = 0);
+        None
+    }
+
+    /// Absolute position of the current data point.
+    #[inline]
+    fn position(&self) -> &[usize] {
+        &self.axis_abs
+    }
+}
+
+/// Advance the multi-dimension-index `indices` in place to the next one within `ranges`, with the
+/// last axis changing fastest.
+///
+/// Returns `false` once all indices have been visited, in which case `indices` wraps around to
+/// the start of the ranges.
+fn next_indices(indices: &mut [usize], ranges: &[Range<usize>]) -> bool {
+    // Increment like an odometer, starting at the last axis
+    for (index, range) in indices.iter_mut().zip(ranges).rev() {
+        *index += 1;
+        if *index < range.end {
+            return true;
+        }
+        *index = range.start;
+    }
+    false
+}
+
+/// Calculate the position of the data point at the multi-dimension-index `indices` in the
+/// native tile-layout of a spectrum.
+///
+/// Use [`TileLayout`] directly when calculating the positions of many data points.
+fn tiled_position(axis_headers: &[AxisHeader], indices: &[usize]) -> usize {
+    TileLayout::new(axis_headers).position(indices)
+}
+
+/// Tile layout of a spectrum, for calculating positions of data points in the native
+/// tile-layout without allocations.
+struct TileLayout {
+    /// Amount of data points along each axis (without padding).
+    axis_sizes: Vec<usize>,
+    /// Amount of tiles along each axis.
+    axis_tiles: Vec<usize>,
+    /// Amount of data points along each axis in a tile, including zero-padding.
+    axis_tile_sizes: Vec<usize>,
+    /// Amount of data points stored per tile.
+    tile_data_points: usize,
+}
+
+impl TileLayout {
+    fn new(axis_headers: &[AxisHeader]) -> Self {
+        let axis_tile_sizes: Vec<_> = axis_headers
             .iter()
-            .zip(&tile_indices)
-            .zip(&self.file.axis_headers)
-            .map(|((tile_size, tile_index), axis_header)| {
-                (*tile_size as u32 - axis_header.tile_padding(*tile_index)) as usize
-            })
+            .map(|axis| axis.tile_size as usize)
             .collect();
 
-        let axis_starts: Vec<_> = axis_tile_sizes
+        Self {
+            axis_sizes: axis_headers
+                .iter()
+                .map(|axis| axis.data_points as usize)
+                .collect(),
+            axis_tiles: axis_headers
+                .iter()
+                .map(|axis| axis.num_tiles() as usize)
+                .collect(),
+            tile_data_points: axis_tile_sizes.iter().product(),
+            axis_tile_sizes,
+        }
+    }
+
+    /// Total amount of tiles.
+    fn num_tiles(&self) -> usize {
+        self.axis_tiles.iter().product()
+    }
+
+    /// Geometry of the tile with the multi-dimension-index `tile_indices` (in tiles).
+    fn geometry(&self, tile_indices: &[usize]) -> TileGeometry {
+        let axis_starts: Vec<_> = tile_indices
+            .iter()
+            .zip(&self.axis_tile_sizes)
+            .map(|(tile_index, tile_size)| tile_index * tile_size)
+            .collect();
+        // Edge tiles are shorter than a normal tile, as they contain padding
+        let axis_lengths = axis_starts
             .iter()
-            .zip(tile_indices)
-            .map(|(tile_size, tile_index)| tile_size * tile_index)
+            .zip(&self.axis_sizes)
+            .zip(&self.axis_tile_sizes)
+            .map(|((start, size), tile_size)| (size - start).min(*tile_size))
             .collect();
 
-        let tile_data_points: usize = this_tile_axis_lens.iter().product();
+        TileGeometry {
+            axis_lengths,
+            axis_starts,
+            padded_axis_lengths: self.axis_tile_sizes.clone(),
+        }
+    }
 
-        let data_range_start = tile_data_points * self.next_index;
-        let data_range_end = data_range_start + tile_data_points;
+    /// Position of the data point at the multi-dimension-index `indices`.
+    fn position(&self, indices: &[usize]) -> usize {
+        let mut tile_pos = 0;
+        let mut tile_rel_pos = 0;
+        for ((index, tiles), tile_size) in indices
+            .iter()
+            .zip(&self.axis_tiles)
+            .zip(&self.axis_tile_sizes)
+        {
+            tile_pos = tile_pos * tiles + index / tile_size;
+            tile_rel_pos = tile_rel_pos * tile_size + index % tile_size;
+        }
 
-        self.next_index += 1;
-        Some(Tile {
-            axis_lengths: this_tile_axis_lens,
-            axis_starts,
-            data: &self.file.data[data_range_start..data_range_end],
-        })
+        tile_pos * self.tile_data_points + tile_rel_pos
     }
 }
 
+/// Construct data in the native tile-layout for a spectrum with `axis_headers`, by calling
+/// `value` with the multi-dimension-index of every data point.
+///
+/// Padding is filled with zeros.
+fn tiled_data_from_fn(
+    axis_headers: &[AxisHeader],
+    mut value: impl FnMut(&[usize]) -> f32,
+) -> Vec<f32> {
+    let layout = TileLayout::new(axis_headers);
+
+    let mut data = vec![];
+    for tile_n in 0..layout.num_tiles() {
+        let geometry = layout.geometry(&multi_dim_index(&layout.axis_tiles, tile_n));
+        let tile_start = data.len();
+        data.resize(tile_start + geometry.data_points(), 0f32);
+
+        let mut positions = TilePositions::new(
+            &geometry.axis_lengths,
+            &geometry.axis_starts,
+            &geometry.padded_axis_lengths,
+        );
+        while let Some(index) = positions.advance() {
+            data[tile_start + index] = value(positions.position());
+        }
+    }
+    data
+}
+
+/// Decode raw big-endian data into the real and (for complex data) imaginary part.
+fn decode_data(bytes: &[u8], components: u8) -> (Vec<f32>, Option<Vec<f32>>) {
+    let mut data: Vec<f32> = bytes
+        .chunks(4)
+        .map(|chunk| f32::from_be_bytes(chunk.try_into().unwrap()))
+        .collect();
+
+    // Complex data points are stored as interleaved real and imaginary values
+    let mut imaginary_data = None;
+    if components == 2 {
+        imaginary_data = Some(data.iter().skip(1).step_by(2).copied().collect());
+        data = data.iter().step_by(2).copied().collect();
+    }
+
+    (data, imaginary_data)
+}
+
 /// Calculate the position in a flat array from multi-dimension-index and dimension sizes.
 fn multi_dim_position(sizes: &[usize], indices: &[usize]) -> usize {
     assert!(sizes.len() == indices.len());
 
     let mut pos = 0;
-    for dim in 0..sizes.len() {
+    for (dim, dim_index) in indices.iter().enumerate() {
         let mut subdimensions_size = 0;
         let subdimensions = sizes.len() - (dim + 1);
         if subdimensions >= 1 {
@@ -557,7 +1285,6 @@ fn multi_dim_position(sizes: &[usize], indices: &[usize]) -> usize {
                 .product();
         }
 
-        let dim_index = indices[dim];
         match subdimensions_size {
             0 => pos += dim_index,
             subdimensions_size => pos += dim_index * subdimensions_size,
@@ -567,20 +1294,15 @@ fn multi_dim_position(sizes: &[usize], indices: &[usize]) -> usize {
     pos
 }
 
+/// Calculate the multi-dimension-index from a position in a flat array and dimension sizes.
+///
+/// Inverse of `multi_dim_position`.
 fn multi_dim_index(sizes: &[usize], pos: usize) -> Vec<usize> {
     let mut indices = [0usize].repeat(sizes.len());
-    // TODO: implement in generic way
-    match sizes.len() {
-        2 => {
-            indices[0] = pos / sizes[1];
-            indices[1] = pos % sizes[1];
-        }
-        3 => {
-            indices[0] = pos / (sizes[1] * sizes[2]);
-            indices[1] = (pos % (sizes[1] * sizes[2])) / sizes[2];
-            indices[2] = (pos % (sizes[1] * sizes[2])) % sizes[2];
-        }
-        _ => unimplemented!(),
+    let mut rem = pos;
+    for (index, size) in indices.iter_mut().zip(sizes).rev() {
+        *index = rem % size;
+        rem /= size;
     }
 
     indices
@@ -611,5 +1333,20 @@ mod test {
         assert_eq!(f(&[4, 3, 2], 19), vec![3, 0, 1]);
         assert_eq!(f(&[4, 3, 2], 20), vec![3, 1, 0]);
         assert_eq!(f(&[4, 3, 2], 21), vec![3, 1, 1]);
+
+        assert_eq!(f(&[7], 5), vec![5]);
+        assert_eq!(f(&[3, 3], 4), vec![1, 1]);
+        assert_eq!(f(&[2, 3, 4, 5], 119), vec![1, 2, 3, 4]);
+        assert_eq!(f(&[2, 3, 4, 5], 67), vec![1, 0, 1, 2]);
+        assert_eq!(f(&[2, 2, 2, 2, 2], 21), vec![1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn multi_dim_index_inverse() {
+        let sizes = [3, 2, 4, 5];
+        for pos in 0..sizes.iter().product() {
+            let indices = super::multi_dim_index(&sizes, pos);
+            assert_eq!(super::multi_dim_position(&sizes, &indices), pos);
+        }
     }
 }
@@ -0,0 +1,96 @@
+//! Parallel decoding and iteration with [`rayon`] (requires the `rayon` feature).
+use std::convert::TryInto;
+
+use rayon::prelude::*;
+
+use crate::{Tile, TileLayout, UcsfError, UcsfFile};
+
+impl UcsfFile {
+    /// Parse a file like [`UcsfFile::parse`], but decode the data on multiple threads.
+    pub fn par_parse(input: &[u8]) -> Result<(&[u8], Self), UcsfError> {
+        let (rem, header, axis_headers, data) = Self::parse_raw(input)?;
+
+        let decode = |chunk: &[u8]| f32::from_be_bytes(chunk.try_into().unwrap());
+        // Complex data points are stored as interleaved real and imaginary values
+        let (data, imaginary_data) = match header.components {
+            2 => (
+                data.par_chunks(8)
+                    .map(|chunk| decode(&chunk[..4]))
+                    .collect(),
+                Some(
+                    data.par_chunks(8)
+                        .map(|chunk| decode(&chunk[4..]))
+                        .collect(),
+                ),
+            ),
+            _ => (data.par_chunks(4).map(decode).collect(), None),
+        };
+
+        Ok((
+            rem,
+            Self {
+                header,
+                axis_headers,
+                data,
+                imaginary_data,
+            },
+        ))
+    }
+
+    /// Returns a parallel iterator over all tiles in the file.
+    pub fn par_tiles(&self) -> impl IndexedParallelIterator<Item = Tile<'_>> {
+        let tiles_total: usize = self.axis_tiles().iter().product();
+        (0..tiles_total)
+            .into_par_iter()
+            .map(move |tile_n| self.tile(tile_n).unwrap())
+    }
+
+    /// Construct a Vec where the data is layed out continously per-axis like
+    /// [`UcsfFile::data_continous`], but on multiple threads.
+    pub fn par_data_continous(&self) -> Vec<f32> {
+        self.par_to_continous(&self.data)
+    }
+
+    /// Construct a Vec where the imaginary data is layed out continously per-axis like
+    /// [`UcsfFile::imaginary_data_continous`], but on multiple threads.
+    pub fn par_imaginary_data_continous(&self) -> Option<Vec<f32>> {
+        Some(self.par_to_continous(self.imaginary_data.as_ref()?))
+    }
+
+    fn par_to_continous(&self, tiled: &[f32]) -> Vec<f32> {
+        let layout = TileLayout::new(&self.axis_headers);
+        // Without axes there are no rows, and the tiled data is already continous
+        let last_axis = match layout.axis_sizes.len().checked_sub(1) {
+            Some(last_axis) => last_axis,
+            None => return tiled.to_vec(),
+        };
+        let row_len = layout.axis_sizes[last_axis];
+        let tile_size = layout.axis_tile_sizes[last_axis];
+
+        let mut data = [0f32].repeat(layout.axis_sizes.iter().product());
+        // Each row along the last axis is filled independently. Inside of a tile, the values
+        // along the last axis are stored continously.
+        data.par_chunks_mut(row_len).enumerate().for_each_init(
+            || [0usize].repeat(layout.axis_sizes.len()),
+            |indices, (row, values)| {
+                let mut rem = row;
+                for (index, size) in indices[..last_axis]
+                    .iter_mut()
+                    .zip(&layout.axis_sizes[..last_axis])
+                    .rev()
+                {
+                    *index = rem % size;
+                    rem /= size;
+                }
+                for tile_start in (0..row_len).step_by(tile_size) {
+                    indices[last_axis] = tile_start;
+                    let start = layout.position(indices);
+                    let len = tile_size.min(row_len - tile_start);
+                    values[tile_start..tile_start + len]
+                        .copy_from_slice(&tiled[start..start + len]);
+                }
+            },
+        );
+        data
+    }
+}
@@ -0,0 +1,293 @@
+//! Peak picking in spectra of any dimensionality.
+use std::ops::Range;
+
+use crate::{multi_dim_index, multi_dim_position, next_indices, AxisRegion, UcsfError, UcsfFile};
+
+/// Threshold a data point has to exceed to be picked as a peak, as used by [`PeakPicker`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// Absolute height. Only the magnitude is used, so the sign doesn't matter.
+    Absolute(f32),
+    /// Multiple of the noise level estimated by [`UcsfFile::noise_level`].
+    NoiseMultiple(f32),
+}
+
+/// Minimum separation between two peaks along a single axis, as used by [`PeakPicker`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Separation {
+    /// Separation in data points.
+    Points(usize),
+    /// Separation in ppm.
+    Ppm(f64),
+}
+
+/// Settings for picking peaks via [`UcsfFile::pick_peaks`].
+///
+/// ```
+/// # use std::fs;
+/// # use ucsf_nmr::{PeakPicker, Separation, Threshold, UcsfFile};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+/// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+/// let picker = PeakPicker::new()
+///     .positive(Threshold::NoiseMultiple(20.0))
+///     .separation(&[Separation::Ppm(0.5), Separation::Ppm(0.05)]);
+/// for peak in ucsf_file.pick_peaks(&picker)? {
+///     format!("{:?} : {}", peak.ppms, peak.height);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PeakPicker {
+    positive: Option<Threshold>,
+    negative: Option<Threshold>,
+    separation: Option<Vec<Separation>>,
+    region: Option<Vec<AxisRegion>>,
+}
+
+impl PeakPicker {
+    /// Create a peak picker without thresholds, which doesn't pick any peaks yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pick local maxima with a height of at least `threshold`.
+    pub fn positive(mut self, threshold: Threshold) -> Self {
+        self.positive = Some(threshold);
+        self
+    }
+
+    /// Pick local minima with a height of at most minus `threshold`.
+    pub fn negative(mut self, threshold: Threshold) -> Self {
+        self.negative = Some(threshold);
+        self
+    }
+
+    /// Set the minimum separation between two peaks along each axis.
+    ///
+    /// Two peaks are too close if they are closer than the separation along all axes, in which case
+    /// only the one with the larger absolute height is kept.
+    pub fn separation(mut self, separation: &[Separation]) -> Self {
+        self.separation = Some(separation.to_vec());
+        self
+    }
+
+    /// Only pick peaks within the region specified by one [`AxisRegion`] per axis.
+    ///
+    /// Data points outside of the region are still taken into account as neighbours.
+    pub fn region(mut self, region: &[AxisRegion]) -> Self {
+        self.region = Some(region.to_vec());
+        self
+    }
+}
+
+/// Peak found by [`UcsfFile::pick_peaks`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Peak {
+    /// Multi-dimension-index of the data point at the peak.
+    pub indices: Vec<usize>,
+    /// ppm position of the data point at the peak.
+    pub ppms: Vec<f64>,
+    pub height: f32,
+}
+
+impl UcsfFile {
+    /// Pick local maxima and/or minima of the real part of the data, as configured by `picker`.
+    ///
+    /// A data point is a local maximum (minimum) if none of its direct neighbours, including the
+    /// diagonal ones, has a larger (smaller) value. Peaks are returned sorted by descending
+    /// absolute height.
+    pub fn pick_peaks(&self, picker: &PeakPicker) -> Result<Vec<Peak>, UcsfError> {
+        let dimensions = self.axis_headers.len();
+        let ranges: Vec<Range<usize>> = match &picker.region {
+            Some(region) => {
+                if region.len() != dimensions {
+                    return Err(UcsfError::DimensionMismatch {
+                        expected: dimensions,
+                        actual: region.len(),
+                    });
+                }
+                region
+                    .iter()
+                    .zip(&self.axis_headers)
+                    .map(|(region, axis_header)| region.index_range(axis_header))
+                    .collect()
+            }
+            None => self.axis_sizes().into_iter().map(|size| 0..size).collect(),
+        };
+        let min_separation = match &picker.separation {
+            Some(separation) => {
+                if separation.len() != dimensions {
+                    return Err(UcsfError::DimensionMismatch {
+                        expected: dimensions,
+                        actual: separation.len(),
+                    });
+                }
+                separation
+                    .iter()
+                    .zip(&self.axis_headers)
+                    .map(|(separation, axis_header)| match separation {
+                        Separation::Points(points) => *points,
+                        Separation::Ppm(ppm) => {
+                            (ppm.abs() / axis_header.ppm_per_point().abs()).round() as usize
+                        }
+                    })
+                    .collect()
+            }
+            None => vec![0; dimensions],
+        };
+
+        let mut noise_level = None;
+        let mut threshold_level = |threshold: Option<Threshold>| match threshold? {
+            Threshold::Absolute(level) => Some(level.abs()),
+            Threshold::NoiseMultiple(multiple) => {
+                if noise_level.is_none() {
+                    noise_level = Some(self.noise_level()?);
+                }
+                Some(multiple.abs() * noise_level?)
+            }
+        };
+        let positive = threshold_level(picker.positive);
+        let negative = threshold_level(picker.negative).map(|level| -level);
+        if positive.is_none() && negative.is_none() {
+            return Ok(vec![]);
+        }
+
+        // Only the region and its direct neighbours are read from the spectrum
+        let block_ranges: Vec<Range<usize>> = ranges
+            .iter()
+            .zip(self.axis_sizes())
+            .map(|(range, size)| range.start.saturating_sub(1)..(range.end + 1).min(size))
+            .collect();
+        let block = self.block_continous(&block_ranges);
+        let block_sizes: Vec<_> = block_ranges.iter().map(|range| range.len()).collect();
+        let neighbours = Neighbours::new(&block_sizes);
+
+        let mut candidates = vec![];
+        let mut indices: Vec<_> = ranges.iter().map(|range| range.start).collect();
+        let mut block_indices = vec![0; dimensions];
+        while ranges.iter().all(|range| !range.is_empty()) {
+            for ((block_index, index), block_range) in
+                block_indices.iter_mut().zip(&indices).zip(&block_ranges)
+            {
+                *block_index = index - block_range.start;
+            }
+            let position = multi_dim_position(&block_sizes, &block_indices);
+            let value = block[position];
+            let is_peak = match (positive, negative) {
+                (Some(positive), _) if value >= positive => {
+                    neighbours.is_extremum(&block, &block_indices, position, 1.0)
+                }
+                (_, Some(negative)) if value <= negative => {
+                    neighbours.is_extremum(&block, &block_indices, position, -1.0)
+                }
+                _ => false,
+            };
+            if is_peak {
+                candidates.push((indices.clone(), value));
+            }
+
+            if !next_indices(&mut indices, &ranges) {
+                break;
+            }
+        }
+
+        candidates.sort_by(|(_, a), (_, b)| b.abs().total_cmp(&a.abs()));
+        let mut peaks: Vec<Peak> = vec![];
+        for (indices, height) in candidates {
+            let too_close = peaks.iter().any(|peak| {
+                peak.indices
+                    .iter()
+                    .zip(&indices)
+                    .zip(&min_separation)
+                    .all(|((a, b), separation)| a.max(b) - a.min(b) < *separation)
+            });
+            if too_close {
+                continue;
+            }
+
+            let ppms = indices
+                .iter()
+                .zip(&self.axis_headers)
+                .map(|(index, axis_header)| axis_header.index_to_ppm(*index as f64))
+                .collect();
+            peaks.push(Peak {
+                indices,
+                ppms,
+                height,
+            });
+        }
+
+        Ok(peaks)
+    }
+}
+
+/// Direct neighbours of the data points in a continous block of data, including the diagonal
+/// ones.
+struct Neighbours {
+    sizes: Vec<usize>,
+    /// Offset (-1, 0 or +1) along each axis and the resulting offset of the position in the
+    /// block, for every neighbour.
+    offsets: Vec<(Vec<isize>, isize)>,
+}
+
+impl Neighbours {
+    fn new(sizes: &[usize]) -> Self {
+        let offset_sizes = vec![3; sizes.len()];
+        let offsets = (0..offset_sizes.iter().product())
+            .map(|offset_position| {
+                let offsets: Vec<_> = multi_dim_index(&offset_sizes, offset_position)
+                    .into_iter()
+                    .map(|offset| offset as isize - 1)
+                    .collect();
+                let position_offset = offsets
+                    .iter()
+                    .zip(sizes)
+                    .fold(0, |position, (offset, size)| {
+                        position * *size as isize + offset
+                    });
+                (offsets, position_offset)
+            })
+            .filter(|(offsets, _)| offsets.iter().any(|offset| *offset != 0))
+            .collect();
+
+        Self {
+            sizes: sizes.to_vec(),
+            offsets,
+        }
+    }
+
+    /// Check that no direct neighbour of the data point at `indices` (with `position` in the
+    /// continous `data`) is larger (`sign` = 1.0) or smaller (`sign` = -1.0).
+    ///
+    /// On a plateau of equal values, only the data point with the lowest position is an extremum.
+    fn is_extremum(&self, data: &[f32], indices: &[usize], position: usize, sign: f32) -> bool {
+        let value = sign * data[position];
+        for (offsets, position_offset) in &self.offsets {
+            let inside =
+                indices
+                    .iter()
+                    .zip(offsets)
+                    .zip(&self.sizes)
+                    .all(|((index, offset), size)| {
+                        let shifted = *index as isize + offset;
+                        shifted >= 0 && shifted < *size as isize
+                    });
+            if !inside {
+                continue;
+            }
+
+            let neighbour_position = (position as isize + position_offset) as usize;
+            let neighbour_value = sign * data[neighbour_position];
+            if neighbour_value > value
+                || (neighbour_value == value && neighbour_position < position)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,176 @@
+//! Projections of spectra along one or more axes.
+use crate::{multi_dim_position, tiled_data_from_fn, AxisRegion, Header, UcsfError, UcsfFile};
+
+/// How the values along the collapsed axes are combined in a projection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectionMode {
+    /// Maximum value (skyline projection).
+    Max,
+    /// Minimum value.
+    Min,
+    /// Value with the largest absolute value (keeping its sign).
+    AbsMax,
+    /// Sum of all values.
+    Sum,
+    /// Mean of all values.
+    Mean,
+}
+
+impl ProjectionMode {
+    fn combine(&self, acc: f64, value: f64) -> f64 {
+        match self {
+            ProjectionMode::Max => acc.max(value),
+            ProjectionMode::Min => acc.min(value),
+            ProjectionMode::AbsMax => match value.abs() > acc.abs() {
+                true => value,
+                false => acc,
+            },
+            ProjectionMode::Sum | ProjectionMode::Mean => acc + value,
+        }
+    }
+
+    fn initial(&self) -> f64 {
+        match self {
+            ProjectionMode::Max => f64::NEG_INFINITY,
+            ProjectionMode::Min => f64::INFINITY,
+            ProjectionMode::AbsMax | ProjectionMode::Sum | ProjectionMode::Mean => 0.0,
+        }
+    }
+}
+
+/// Treatment of a single axis, as used by [`UcsfFile::project`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AxisProjection {
+    /// The axis is kept in the projection.
+    Keep,
+    /// The axis is collapsed, using only the data points in the region.
+    Collapse(AxisRegion),
+}
+
+impl UcsfFile {
+    /// Project the spectrum along all [`AxisProjection::Collapse`] axes.
+    ///
+    /// The returned spectrum contains the axis headers of the kept axes.
+    /// Only the real part of complex data is projected.
+    /// Similar to `ucsfdata -p` in Sparky.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use ucsf_nmr::{AxisProjection, AxisRegion, ProjectionMode, UcsfFile};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+    /// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+    /// // 1H skyline projection of the amide region
+    /// let projection = ucsf_file.project(
+    ///     &[
+    ///         AxisProjection::Collapse(AxisRegion::Ppm(105.0, 130.0)),
+    ///         AxisProjection::Keep,
+    ///     ],
+    ///     ProjectionMode::Max,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn project(
+        &self,
+        axes: &[AxisProjection],
+        mode: ProjectionMode,
+    ) -> Result<UcsfFile, UcsfError> {
+        if axes.len() != self.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: axes.len(),
+            });
+        }
+        if !axes.contains(&AxisProjection::Keep) {
+            return Err(UcsfError::NoAxesKept);
+        }
+
+        // Range of data points along each axis that contributes to the projection
+        let ranges: Vec<_> = axes
+            .iter()
+            .zip(&self.axis_headers)
+            .map(|(projection, axis_header)| match projection {
+                AxisProjection::Keep => AxisRegion::Full.index_range(axis_header),
+                AxisProjection::Collapse(region) => region.index_range(axis_header),
+            })
+            .collect();
+        if let Some(axis) = ranges.iter().position(|range| range.is_empty()) {
+            return Err(UcsfError::EmptyRegion(axis));
+        }
+
+        let kept_axes: Vec<_> = axes
+            .iter()
+            .enumerate()
+            .filter(|(_, projection)| **projection == AxisProjection::Keep)
+            .map(|(axis, _)| axis)
+            .collect();
+        let axis_headers: Vec<_> = kept_axes
+            .iter()
+            .map(|axis| self.axis_headers[*axis].clone())
+            .collect();
+        let kept_sizes: Vec<_> = axis_headers
+            .iter()
+            .map(|axis| axis.data_points as usize)
+            .collect();
+
+        let mut projected = vec![mode.initial(); kept_sizes.iter().product()];
+        let mut kept_indices = vec![0; kept_axes.len()];
+        for tile in self.tiles() {
+            let intersects = tile
+                .axis_starts
+                .iter()
+                .zip(&tile.axis_lengths)
+                .zip(&ranges)
+                .all(|((start, length), range)| *start < range.end && start + length > range.start);
+            if !intersects {
+                continue;
+            }
+
+            let mut values = tile.iter_with_abolute_pos();
+            while let Some((axis_indices, value)) = values.next_with_pos() {
+                let inside = axis_indices
+                    .iter()
+                    .zip(&ranges)
+                    .all(|(index, range)| range.contains(index));
+                if !inside {
+                    continue;
+                }
+
+                for (kept_index, axis) in kept_indices.iter_mut().zip(&kept_axes) {
+                    *kept_index = axis_indices[*axis];
+                }
+                let pos = multi_dim_position(&kept_sizes, &kept_indices);
+                projected[pos] = mode.combine(projected[pos], value as f64);
+            }
+        }
+
+        if mode == ProjectionMode::Mean {
+            let collapsed_points: usize = axes
+                .iter()
+                .zip(&ranges)
+                .filter(|(projection, _)| **projection != AxisProjection::Keep)
+                .map(|(_, range)| range.len())
+                .product();
+            for value in projected.iter_mut() {
+                *value /= collapsed_points as f64;
+            }
+        }
+
+        let data = tiled_data_from_fn(&axis_headers, |indices| {
+            projected[multi_dim_position(&kept_sizes, indices)] as f32
+        });
+
+        Ok(UcsfFile {
+            header: Header {
+                dimensions: axis_headers.len() as u8,
+                components: 1,
+                ..self.header.clone()
+            },
+            axis_headers,
+            data,
+            imaginary_data: None,
+        })
+    }
+}
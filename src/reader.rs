@@ -0,0 +1,187 @@
+//! Lazy reading of spectra from a [`Read`] + [`Seek`] source.
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::{decode_data, AxisHeader, Header, Tile, TileGeometry, UcsfError};
+
+/// Reader that parses the headers of a spectrum eagerly, but only loads and decodes
+/// individual tiles when requested.
+///
+/// Useful for spectra that are too large to comfortably keep in memory as a whole.
+///
+/// ```
+/// # use std::fs::File;
+/// # use ucsf_nmr::UcsfReader;
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// let mut reader = UcsfReader::new(File::open("./tests/data/15n_hsqc.ucsf")?)?;
+/// let tile = reader.read_tile(3)?;
+/// for (axis_indices, value) in tile.tile().iter_with_abolute_pos() {
+///     format!("{:?} : {}", axis_indices, value);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct UcsfReader<R> {
+    reader: R,
+    header: Header,
+    axis_headers: Vec<AxisHeader>,
+}
+
+impl<R: Read + Seek> UcsfReader<R> {
+    /// Create a reader by parsing the header and axis headers from the start of `reader`.
+    pub fn new(mut reader: R) -> Result<Self, UcsfError> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut header_bytes = [0u8; 180];
+        reader.read_exact(&mut header_bytes)?;
+        let (_, header) = Header::parse(&header_bytes)?;
+
+        let mut axis_headers = vec![];
+        for _ in 0..header.dimensions {
+            let mut axis_header_bytes = [0u8; 128];
+            reader.read_exact(&mut axis_header_bytes)?;
+            let (_, axis_header) = AxisHeader::parse(&axis_header_bytes)?;
+            axis_headers.push(axis_header);
+        }
+
+        Ok(Self {
+            reader,
+            header,
+            axis_headers,
+        })
+    }
+
+    /// Read and decode the tile with index `tile_n`.
+    ///
+    /// Tiles are numbered in the same order as they are returned by [`UcsfFile::tiles`](crate::UcsfFile::tiles).
+    pub fn read_tile(&mut self, tile_n: usize) -> Result<OwnedTile, UcsfError> {
+        if tile_n >= self.num_tiles() {
+            return Err(UcsfError::TileOutOfRange);
+        }
+
+        let geometry = TileGeometry::for_tile(&self.axis_headers, tile_n);
+        let components = self.header.components as usize;
+        // * 4 as each data point is a f32
+        let tile_bytes = geometry.data_points() * components * 4;
+        let data_start = 180 + 128 * self.axis_headers.len() + tile_bytes * tile_n;
+
+        let mut bytes = [0u8].repeat(tile_bytes);
+        self.reader.seek(SeekFrom::Start(data_start as u64))?;
+        self.reader.read_exact(&mut bytes)?;
+
+        Ok(OwnedTile::decode(geometry, &bytes, self.header.components))
+    }
+
+    /// Returns an iterator that reads all tiles one after another.
+    pub fn tiles(&mut self) -> ReaderTiles<'_, R> {
+        ReaderTiles {
+            next_index: 0,
+            reader: self,
+        }
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn axis_headers(&self) -> &[AxisHeader] {
+        &self.axis_headers
+    }
+
+    /// Returns the total amount of tiles in the file.
+    pub fn num_tiles(&self) -> usize {
+        self.axis_tiles().iter().product()
+    }
+
+    /// Returns the amount of tiles along each axis.
+    pub fn axis_tiles(&self) -> Vec<usize> {
+        self.axis_headers
+            .iter()
+            .map(|axis| axis.num_tiles() as usize)
+            .collect()
+    }
+
+    /// Returns the amount of data points in a tile along all axis.
+    pub fn axis_tile_sizes(&self) -> Vec<usize> {
+        self.axis_headers
+            .iter()
+            .map(|axis| axis.tile_size as usize)
+            .collect()
+    }
+
+    /// Returns the sizes for all axis.
+    pub fn axis_sizes(&self) -> Vec<usize> {
+        self.axis_headers
+            .iter()
+            .map(|axis| axis.data_points as usize)
+            .collect()
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Tile that owns its decoded data, as returned by [`UcsfReader`].
+#[derive(Debug, Clone)]
+pub struct OwnedTile {
+    /// Amount of data points along each axis in this tile.
+    pub axis_lengths: Vec<usize>,
+    /// Index of first element along each axis (in relation to total axis).
+    pub axis_starts: Vec<usize>,
+    /// Amount of data points along each axis in this tile, including zero-padding.
+    pub padded_axis_lengths: Vec<usize>,
+    /// Data of the tile (including zero-padding)
+    pub data: Vec<f32>,
+    /// Imaginary data of the tile (including zero-padding)
+    ///
+    /// Only present for files with complex data.
+    pub imaginary_data: Option<Vec<f32>>,
+}
+
+impl OwnedTile {
+    pub(crate) fn decode(geometry: TileGeometry, bytes: &[u8], components: u8) -> Self {
+        let (data, imaginary_data) = decode_data(bytes, components);
+
+        Self {
+            axis_lengths: geometry.axis_lengths,
+            axis_starts: geometry.axis_starts,
+            padded_axis_lengths: geometry.padded_axis_lengths,
+            data,
+            imaginary_data,
+        }
+    }
+
+    /// Borrow the tile as a [`Tile`], which provides access to iterators over its values.
+    pub fn tile(&self) -> Tile<'_> {
+        Tile {
+            axis_lengths: self.axis_lengths.clone(),
+            axis_starts: self.axis_starts.clone(),
+            padded_axis_lengths: self.padded_axis_lengths.clone(),
+            data: &self.data,
+            imaginary_data: self.imaginary_data.as_deref(),
+        }
+    }
+}
+
+/// Iterator over all tiles of a [`UcsfReader`].
+pub struct ReaderTiles<'a, R> {
+    next_index: usize,
+    reader: &'a mut UcsfReader<R>,
+}
+
+impl<'a, R: Read + Seek> Iterator for ReaderTiles<'a, R> {
+    type Item = Result<OwnedTile, UcsfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_index >= self.reader.num_tiles() {
+            return None;
+        }
+
+        let tile = self.reader.read_tile(self.next_index);
+        self.next_index += 1;
+        Some(tile)
+    }
+}
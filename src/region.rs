@@ -0,0 +1,180 @@
+//! Extraction of sub-spectra.
+use std::ops::Range;
+
+use crate::{next_indices, AxisHeader, TileLayout, UcsfError, UcsfFile};
+
+/// Region along a single axis, as used by [`UcsfFile::region`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AxisRegion {
+    /// All data points along the axis.
+    Full,
+    /// Data points with indices in the range (clamped to the size of the axis).
+    Index(Range<usize>),
+    /// Data points with ppm values between the two bounds (inclusive, in any order).
+    Ppm(f64, f64),
+}
+
+impl AxisRegion {
+    /// Resolve the region to a range of data point indices along `axis_header`.
+    pub fn index_range(&self, axis_header: &AxisHeader) -> Range<usize> {
+        let data_points = axis_header.data_points as usize;
+        match self {
+            AxisRegion::Full => 0..data_points,
+            AxisRegion::Index(range) => {
+                let end = range.end.min(data_points);
+                range.start.min(end)..end
+            }
+            AxisRegion::Ppm(ppm_a, ppm_b) => axis_header.ppm_to_index_range(*ppm_a, *ppm_b),
+        }
+    }
+}
+
+impl AxisHeader {
+    /// Returns the axis header for the data points in `range` along this axis.
+    ///
+    /// The spectral width and center are adjusted, so that the data points keep their ppm values.
+    /// The tile size is kept, unless it is larger than the new amount of data points.
+    pub(crate) fn region(&self, range: Range<usize>) -> Self {
+        let data_points = range.len() as u32;
+        let center = self.index_to_ppm(range.start as f64 + data_points as f64 / 2.0);
+
+        Self {
+            nucleus_name: self.nucleus_name.clone(),
+            data_points,
+            unknown: self.unknown,
+            tile_size: self.tile_size.min(data_points),
+            frequency: self.frequency,
+            spectral_width: (self.hz_per_point() * data_points as f64) as f32,
+            center: center as f32,
+            remainder: self.remainder.clone(),
+        }
+    }
+}
+
+impl UcsfFile {
+    /// Read the real data points within the index `ranges` along each axis, layed out
+    /// continously per-axis.
+    ///
+    /// Only the data points within the ranges are read, which is cheaper than
+    /// [`UcsfFile::data_continous`] for small blocks.
+    pub(crate) fn block_continous(&self, ranges: &[Range<usize>]) -> Vec<f32> {
+        let mut block = Vec::with_capacity(ranges.iter().map(|range| range.len()).product());
+        if ranges.iter().any(|range| range.is_empty()) {
+            return block;
+        }
+
+        let layout = TileLayout::new(&self.axis_headers);
+        let mut indices: Vec<_> = ranges.iter().map(|range| range.start).collect();
+        loop {
+            block.push(self.data[layout.position(&indices)]);
+            if !next_indices(&mut indices, ranges) {
+                return block;
+            }
+        }
+    }
+
+    /// Extract the sub-spectrum in the region specified by one [`AxisRegion`] per axis.
+    ///
+    /// Similar to `ucsfdata -r` in Sparky.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use ucsf_nmr::{AxisRegion, UcsfFile};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+    /// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+    /// // Amide region of a 15N HSQC
+    /// let amides = ucsf_file.region(&[
+    ///     AxisRegion::Ppm(105.0, 130.0),
+    ///     AxisRegion::Ppm(6.0, 10.0),
+    /// ])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn region(&self, regions: &[AxisRegion]) -> Result<UcsfFile, UcsfError> {
+        if regions.len() != self.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: regions.len(),
+            });
+        }
+
+        let ranges: Vec<_> = regions
+            .iter()
+            .zip(&self.axis_headers)
+            .map(|(region, axis_header)| region.index_range(axis_header))
+            .collect();
+        if let Some(axis) = ranges.iter().position(|range| range.is_empty()) {
+            return Err(UcsfError::EmptyRegion(axis));
+        }
+
+        let axis_headers: Vec<_> = self
+            .axis_headers
+            .iter()
+            .zip(&ranges)
+            .map(|(axis_header, range)| axis_header.region(range.clone()))
+            .collect();
+
+        let total_size = axis_headers
+            .iter()
+            .map(|axis| axis.padded_size() as usize)
+            .product();
+        let mut data = [0f32].repeat(total_size);
+        let mut imaginary_data = self
+            .imaginary_data
+            .as_ref()
+            .map(|_| [0f32].repeat(total_size));
+
+        let layout = TileLayout::new(&axis_headers);
+        let mut region_indices = vec![0; ranges.len()];
+        for tile in self.tiles() {
+            let intersects = tile
+                .axis_starts
+                .iter()
+                .zip(&tile.axis_lengths)
+                .zip(&ranges)
+                .all(|((start, length), range)| *start < range.end && start + length > range.start);
+            if !intersects {
+                continue;
+            }
+
+            let mut imaginary_values = tile.iter_imaginary_with_abolute_pos();
+            let mut values = tile.iter_with_abolute_pos();
+            while let Some((axis_indices, value)) = values.next_with_pos() {
+                let imaginary_value = imaginary_values
+                    .as_mut()
+                    .and_then(|values| values.next_with_pos())
+                    .map(|(_, value)| value);
+
+                let inside = axis_indices
+                    .iter()
+                    .zip(&ranges)
+                    .all(|(index, range)| range.contains(index));
+                if !inside {
+                    continue;
+                }
+
+                for ((region_index, index), range) in
+                    region_indices.iter_mut().zip(axis_indices).zip(&ranges)
+                {
+                    *region_index = index - range.start;
+                }
+                let pos = layout.position(&region_indices);
+                data[pos] = value;
+                if let (Some(imaginary_data), Some(imaginary_value)) =
+                    (imaginary_data.as_mut(), imaginary_value)
+                {
+                    imaginary_data[pos] = imaginary_value;
+                }
+            }
+        }
+
+        Ok(UcsfFile {
+            header: self.header.clone(),
+            axis_headers,
+            data,
+            imaginary_data,
+        })
+    }
+}
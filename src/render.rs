@@ -0,0 +1,287 @@
+//! Rendering of 2D spectra to PNG and SVG files.
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::{UcsfError, UcsfFile};
+
+/// Default size of rendered figures in pixels.
+const DEFAULT_SIZE: (u32, u32) = (800, 600);
+
+/// Colormap of a rendered spectrum, as used by [`RenderOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    /// White to black.
+    Greys,
+    /// Dark blue over green to yellow.
+    Viridis,
+    /// Blue over white to red, for spectra with positive and negative signals.
+    RedBlue,
+}
+
+impl Colormap {
+    fn control_points(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            Colormap::Greys => &[(255, 255, 255), (0, 0, 0)],
+            Colormap::Viridis => &[
+                (68, 1, 84),
+                (59, 82, 139),
+                (33, 145, 140),
+                (94, 201, 98),
+                (253, 231, 37),
+            ],
+            Colormap::RedBlue => &[
+                (5, 48, 97),
+                (146, 197, 222),
+                (255, 255, 255),
+                (244, 165, 130),
+                (103, 0, 31),
+            ],
+        }
+    }
+
+    /// Whether the colormap is centered at zero.
+    fn is_diverging(self) -> bool {
+        self == Colormap::RedBlue
+    }
+
+    /// Color at the position `t` (from 0.0 to 1.0) in the colormap.
+    fn color(self, t: f64) -> RGBColor {
+        let points = self.control_points();
+        let position = t.clamp(0.0, 1.0) * (points.len() - 1) as f64;
+        let lower = (position.floor() as usize).min(points.len() - 2);
+        let fraction = position - lower as f64;
+        let (a, b) = (points[lower], points[lower + 1]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+        RGBColor(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
+}
+
+/// How a spectrum is drawn, as used by [`RenderOptions`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderStyle {
+    /// Contour lines at the levels from [`UcsfFile::auto_contour_levels`].
+    AutoContour,
+    /// Contour lines at the given levels.
+    Contour(Vec<f32>),
+    /// Every data point as a rectangle, colored by its value.
+    Image,
+}
+
+/// Settings for rendering a spectrum via [`UcsfFile::render_png`] or [`UcsfFile::render_svg`].
+///
+/// ```no_run
+/// # use std::fs;
+/// # use ucsf_nmr::{Colormap, RenderOptions, RenderStyle, UcsfFile};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+/// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+/// let options = RenderOptions::new(RenderStyle::AutoContour).title("15N HSQC");
+/// ucsf_file.render_svg("hsqc.svg", &options)?;
+///
+/// let options = RenderOptions::new(RenderStyle::Image).colormap(Colormap::Viridis);
+/// ucsf_file.render_png("hsqc.png", &options)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    style: RenderStyle,
+    colormap: Colormap,
+    size: (u32, u32),
+    title: Option<String>,
+    value_range: Option<(f32, f32)>,
+}
+
+impl RenderOptions {
+    pub fn new(style: RenderStyle) -> Self {
+        Self {
+            style,
+            colormap: Colormap::RedBlue,
+            size: DEFAULT_SIZE,
+            title: None,
+            value_range: None,
+        }
+    }
+
+    /// Set the colormap (default: [`Colormap::RedBlue`]).
+    ///
+    /// For contour plots, positive levels are drawn with the upper half of the colormap and
+    /// negative levels with the lower half, with the colors moving outwards for higher levels.
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Set the width and height of the figure in pixels (default: 800 x 600).
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Set the range of values that is spread over the colormap in images.
+    ///
+    /// Values outside of the range get the color of the closest bound. By default the range is
+    /// given by [`UcsfFile::bounds`], or symmetric around zero for diverging colormaps.
+    pub fn value_range(mut self, min: f32, max: f32) -> Self {
+        self.value_range = Some((min, max));
+        self
+    }
+}
+
+impl UcsfFile {
+    /// Render a 2D spectrum to a PNG file at `path`.
+    ///
+    /// The first axis is drawn vertically and the second axis horizontally, with ppm values
+    /// decreasing towards the top and right, as is convention for NMR spectra.
+    pub fn render_png<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &RenderOptions,
+    ) -> Result<(), UcsfError> {
+        let root = BitMapBackend::new(path.as_ref(), options.size).into_drawing_area();
+        self.render(root, options)
+    }
+
+    /// Render a 2D spectrum to a SVG file at `path`.
+    ///
+    /// See [`UcsfFile::render_png`] for the layout.
+    pub fn render_svg<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &RenderOptions,
+    ) -> Result<(), UcsfError> {
+        let root = SVGBackend::new(path.as_ref(), options.size).into_drawing_area();
+        self.render(root, options)
+    }
+
+    fn render<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        options: &RenderOptions,
+    ) -> Result<(), UcsfError> {
+        if self.axis_headers.len() != 2 {
+            return Err(UcsfError::DimensionMismatch {
+                expected: 2,
+                actual: self.axis_headers.len(),
+            });
+        }
+        let render_error =
+            |err: DrawingAreaErrorKind<DB::ErrorType>| UcsfError::Render(err.to_string());
+
+        // ppm values are negated, so that they decrease along the axes
+        let (vertical, horizontal) = (&self.axis_headers[0], &self.axis_headers[1]);
+        let (vertical_min, vertical_max) = vertical.ppm_range();
+        let (horizontal_min, horizontal_max) = horizontal.ppm_range();
+
+        root.fill(&WHITE).map_err(render_error)?;
+        let mut builder = ChartBuilder::on(&root);
+        builder
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60);
+        if let Some(title) = &options.title {
+            builder.caption(title, ("sans-serif", 20));
+        }
+        let mut chart = builder
+            .build_ranged(
+                -horizontal_max..-horizontal_min,
+                -vertical_max..-vertical_min,
+            )
+            .map_err(render_error)?;
+
+        let label = |ppm: &f64| format!("{:.1}", -ppm);
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .x_desc(format!("{} (ppm)", horizontal.nucleus_name))
+            .y_desc(format!("{} (ppm)", vertical.nucleus_name))
+            .x_label_formatter(&label)
+            .y_label_formatter(&label)
+            .draw()
+            .map_err(render_error)?;
+
+        match &options.style {
+            RenderStyle::Image => {
+                let (min, max) = match (options.value_range, options.colormap.is_diverging()) {
+                    (Some(range), _) => range,
+                    (None, false) => self.bounds(),
+                    (None, true) => {
+                        let (min, max) = self.bounds();
+                        let extent = min.abs().max(max.abs());
+                        (-extent, extent)
+                    }
+                };
+                let (vertical_step, horizontal_step) = (
+                    vertical.ppm_per_point() / 2.0,
+                    horizontal.ppm_per_point() / 2.0,
+                );
+                let data = self.data_continous();
+                let columns = horizontal.data_points as usize;
+                let rectangles = data.iter().enumerate().map(|(pos, value)| {
+                    let row_ppm = vertical.index_to_ppm((pos / columns) as f64);
+                    let column_ppm = horizontal.index_to_ppm((pos % columns) as f64);
+                    let t = (*value as f64 - min as f64) / (max as f64 - min as f64);
+                    Rectangle::new(
+                        [
+                            (-column_ppm - horizontal_step, -row_ppm - vertical_step),
+                            (-column_ppm + horizontal_step, -row_ppm + vertical_step),
+                        ],
+                        options.colormap.color(t).filled(),
+                    )
+                });
+                chart.draw_series(rectangles).map_err(render_error)?;
+            }
+            RenderStyle::Contour(_) | RenderStyle::AutoContour => {
+                let levels = match &options.style {
+                    RenderStyle::Contour(levels) => levels.clone(),
+                    _ => self.auto_contour_levels().levels(),
+                };
+                for contour in self.contours(&levels)? {
+                    let mut points: Vec<_> = contour
+                        .points
+                        .iter()
+                        .map(|(vertical_ppm, horizontal_ppm)| (-horizontal_ppm, -vertical_ppm))
+                        .collect();
+                    if contour.closed {
+                        points.push(points[0]);
+                    }
+                    chart
+                        .draw_series(std::iter::once(PathElement::new(
+                            points,
+                            &contour_color(options.colormap, &levels, contour.level),
+                        )))
+                        .map_err(render_error)?;
+                }
+            }
+        }
+
+        root.present().map_err(render_error)?;
+        Ok(())
+    }
+}
+
+/// Color of the contour line at `level`, moving outwards from the center of the `colormap` with
+/// the rank of the level among the `levels` of the same sign.
+fn contour_color(colormap: Colormap, levels: &[f32], level: f32) -> RGBColor {
+    let same_sign: Vec<_> = levels
+        .iter()
+        .filter(|other| (**other >= 0.0) == (level >= 0.0))
+        .collect();
+    let rank = same_sign
+        .iter()
+        .filter(|other| other.abs() < level.abs())
+        .count();
+    let offset = 0.5 * (rank + 1) as f64 / same_sign.len() as f64;
+    match level >= 0.0 {
+        true => colormap.color(0.5 + offset),
+        false => colormap.color(0.5 - offset),
+    }
+}
@@ -0,0 +1,209 @@
+//! Resampling of spectra onto a different ppm grid.
+use crate::{multi_dim_position, tiled_data_from_fn, AxisHeader, UcsfError, UcsfFile};
+
+/// Tolerance in data points, so that target points on the border of the source window, which
+/// are off by rounding errors, are still treated as inside.
+const BORDER_TOLERANCE: f64 = 1e-6;
+
+/// Source data points and their weights for a target data point, or `None` if the target data
+/// point lies outside of the source spectrum.
+type Weights = Option<Vec<(usize, f64)>>;
+
+/// Interpolation between the data points of the source spectrum, as used by
+/// [`UcsfFile::resample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation between the two neighbouring data points.
+    Linear,
+    /// Cubic (Catmull-Rom) interpolation using the four surrounding data points.
+    ///
+    /// At the border of the spectrum, the missing data points are replaced by the border value.
+    Cubic,
+}
+
+/// Value of target data points outside of the source spectrum, as used by
+/// [`UcsfFile::resample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutsideValue {
+    Zero,
+    Nan,
+}
+
+impl OutsideValue {
+    fn value(self) -> f32 {
+        match self {
+            OutsideValue::Zero => 0.0,
+            OutsideValue::Nan => f32::NAN,
+        }
+    }
+}
+
+impl Interpolation {
+    /// Source data points and their weights for the target data point at the fractional source
+    /// `index`, which has to lie within `0..=data_points - 1`.
+    fn weights(self, index: f64, data_points: usize) -> Vec<(usize, f64)> {
+        let lower = (index.floor() as usize).min(data_points - 1);
+        let t = index - lower as f64;
+        let neighbour =
+            |offset: isize| (lower as isize + offset).clamp(0, data_points as isize - 1) as usize;
+
+        let weights = match self {
+            Interpolation::Linear => vec![(neighbour(0), 1.0 - t), (neighbour(1), t)],
+            Interpolation::Cubic => {
+                let (t2, t3) = (t * t, t * t * t);
+                vec![
+                    (neighbour(-1), (-t3 + 2.0 * t2 - t) / 2.0),
+                    (neighbour(0), (3.0 * t3 - 5.0 * t2 + 2.0) / 2.0),
+                    (neighbour(1), (-3.0 * t3 + 4.0 * t2 + t) / 2.0),
+                    (neighbour(2), (t3 - t2) / 2.0),
+                ]
+            }
+        };
+        // Skip data points without influence, so that they can't spread NaN values
+        weights
+            .into_iter()
+            .filter(|(_, weight)| *weight != 0.0)
+            .collect()
+    }
+}
+
+impl UcsfFile {
+    /// Resample the spectrum onto the ppm grid given by `axis_headers`, e.g. to compare
+    /// spectra recorded with different amounts of data points, spectral widths or centers.
+    ///
+    /// The resulting spectrum uses `axis_headers`, including their tile sizes. Every target data
+    /// point is interpolated from the data points surrounding its ppm position, while target data
+    /// points outside of the source spectrum get the `outside` value. The nuclei of the axes
+    /// have to match.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use ucsf_nmr::{Interpolation, OutsideValue, UcsfFile};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+    /// #   let (_remaining_bytes, reference) = UcsfFile::parse(&file_bytes)?;
+    /// #   let mut axis_headers = reference.axis_headers.clone();
+    /// #   axis_headers[1].center += 0.01;
+    /// #   let sample = reference.resample(&axis_headers, Interpolation::Linear, OutsideValue::Zero)?;
+    /// let resampled = sample.resample_like(&reference, Interpolation::Cubic, OutsideValue::Zero)?;
+    /// let difference = resampled.subtract(&reference)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resample(
+        &self,
+        axis_headers: &[AxisHeader],
+        interpolation: Interpolation,
+        outside: OutsideValue,
+    ) -> Result<UcsfFile, UcsfError> {
+        if axis_headers.len() != self.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: axis_headers.len(),
+            });
+        }
+        for (axis, (source, target)) in self.axis_headers.iter().zip(axis_headers).enumerate() {
+            if target.data_points == 0 || target.tile_size == 0 {
+                return Err(UcsfError::InvalidAxisSize(axis));
+            }
+            if source.nucleus_name.trim() != target.nucleus_name.trim() {
+                return Err(UcsfError::IncompatibleAxis {
+                    axis,
+                    property: "nucleus",
+                });
+            }
+        }
+
+        let axis_weights: Vec<Vec<Weights>> = self
+            .axis_headers
+            .iter()
+            .zip(axis_headers)
+            .map(|(source, target)| {
+                let last = (source.data_points - 1) as f64;
+                (0..target.data_points)
+                    .map(|target_index| {
+                        let index = source.ppm_to_index(target.index_to_ppm(target_index as f64));
+                        if index < -BORDER_TOLERANCE || index > last + BORDER_TOLERANCE {
+                            return None;
+                        }
+                        Some(
+                            interpolation
+                                .weights(index.clamp(0.0, last), source.data_points as usize),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let resample_data = |continous: Vec<f32>| {
+            let mut sizes = self.axis_sizes();
+            let mut data = continous;
+            for (axis, weights) in axis_weights.iter().enumerate() {
+                data = resample_axis(&data, &sizes, axis, weights);
+                sizes[axis] = weights.len();
+            }
+
+            tiled_data_from_fn(axis_headers, |indices| {
+                let is_outside = indices
+                    .iter()
+                    .zip(&axis_weights)
+                    .any(|(index, weights)| weights[*index].is_none());
+                match is_outside {
+                    true => outside.value(),
+                    false => data[multi_dim_position(&sizes, indices)],
+                }
+            })
+        };
+
+        Ok(UcsfFile {
+            header: self.header.clone(),
+            axis_headers: axis_headers.to_vec(),
+            data: resample_data(self.data_continous()),
+            imaginary_data: self.imaginary_data_continous().map(resample_data),
+        })
+    }
+
+    /// Resample the spectrum onto the ppm grid of `other`.
+    ///
+    /// The result is compatible with `other` (see [`UcsfFile::check_compatible`]), so that both
+    /// can be used together in arithmetic. See [`UcsfFile::resample`] for details.
+    pub fn resample_like(
+        &self,
+        other: &UcsfFile,
+        interpolation: Interpolation,
+        outside: OutsideValue,
+    ) -> Result<UcsfFile, UcsfError> {
+        self.resample(&other.axis_headers, interpolation, outside)
+    }
+}
+
+/// Interpolate the continous `data` with the given axis `sizes` along `axis`, using the source
+/// data points and their weights for every target data point.
+///
+/// Target data points outside of the source spectrum are set to zero.
+fn resample_axis(data: &[f32], sizes: &[usize], axis: usize, weights: &[Weights]) -> Vec<f32> {
+    let outer: usize = sizes[..axis].iter().product();
+    let inner: usize = sizes[axis + 1..].iter().product();
+    let source_size = sizes[axis];
+
+    let mut resampled = Vec::with_capacity(outer * weights.len() * inner);
+    for outer_pos in 0..outer {
+        for target_weights in weights {
+            for inner_pos in 0..inner {
+                let value = match target_weights {
+                    None => 0.0,
+                    Some(target_weights) => target_weights
+                        .iter()
+                        .map(|(index, weight)| {
+                            weight
+                                * data[(outer_pos * source_size + index) * inner + inner_pos] as f64
+                        })
+                        .sum::<f64>() as f32,
+                };
+                resampled.push(value);
+            }
+        }
+    }
+    resampled
+}
@@ -0,0 +1,195 @@
+//! Extraction of lower-dimensional slices through a spectrum.
+use crate::{tiled_data_from_fn, AxisHeader, Header, TileLayout, UcsfError, UcsfFile};
+
+/// Position along a single axis, as used by [`UcsfFile::plane`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisPosition {
+    /// The axis is kept in the extracted slice.
+    Free,
+    /// The axis is fixed at the data point with the index.
+    Index(usize),
+    /// The axis is fixed at the data point closest to the ppm value.
+    Ppm(f64),
+}
+
+/// 1D slice through a spectrum along a single axis.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    /// Header of the axis the trace runs along.
+    pub axis_header: AxisHeader,
+    /// ppm value of every data point in the trace.
+    pub ppm_scale: Vec<f64>,
+    /// Value of every data point in the trace.
+    pub values: Vec<f32>,
+}
+
+impl UcsfFile {
+    /// Extract the 1D trace along `axis` through the data point at `point`.
+    ///
+    /// The index of `point` along `axis` is ignored. Only the tiles crossed by the trace are read.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use ucsf_nmr::UcsfFile;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+    /// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+    /// // 15N column at the 1H data point 100
+    /// let trace = ucsf_file.trace(0, &[0, 100])?;
+    /// for (ppm, value) in trace.ppm_scale.iter().zip(&trace.values) {
+    ///     format!("{} ppm : {}", ppm, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn trace(&self, axis: usize, point: &[usize]) -> Result<Trace, UcsfError> {
+        if axis >= self.axis_headers.len() {
+            return Err(UcsfError::InvalidAxis(axis));
+        }
+        let mut indices = point.to_vec();
+        if let Some(index) = indices.get_mut(axis) {
+            *index = 0;
+        }
+        self.check_point(&indices)?;
+
+        let axis_header = &self.axis_headers[axis];
+        let layout = TileLayout::new(&self.axis_headers);
+        let values = (0..axis_header.data_points as usize)
+            .map(|index| {
+                indices[axis] = index;
+                self.data[layout.position(&indices)]
+            })
+            .collect();
+
+        Ok(Trace {
+            axis_header: axis_header.clone(),
+            ppm_scale: axis_header.ppm_scale(),
+            values,
+        })
+    }
+
+    /// Extract the 1D trace along `axis` through the data point closest to the ppm position
+    /// `ppms`.
+    ///
+    /// The ppm value along `axis` is ignored.
+    pub fn trace_ppm(&self, axis: usize, ppms: &[f64]) -> Result<Trace, UcsfError> {
+        let axis_header = self
+            .axis_headers
+            .get(axis)
+            .ok_or(UcsfError::InvalidAxis(axis))?;
+        let mut ppms = ppms.to_vec();
+        if let Some(ppm) = ppms.get_mut(axis) {
+            *ppm = axis_header.center as f64;
+        }
+        if ppms.len() != self.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: ppms.len(),
+            });
+        }
+        let point = self.ppm_to_indices(&ppms).ok_or(UcsfError::OutOfBounds)?;
+
+        self.trace(axis, &point)
+    }
+
+    /// Extract the 2D plane of a spectrum with more than two dimensions.
+    ///
+    /// `axes` contains one [`AxisPosition`] per axis, of which exactly two have to be
+    /// [`AxisPosition::Free`]. The other axes are fixed at the given positions. The returned
+    /// spectrum contains the axis headers of the free axes. Only the tiles intersecting the
+    /// plane are read.
+    ///
+    /// ```
+    /// # use ucsf_nmr::{AxisPosition, UcsfFile};
+    /// #
+    /// # fn plane(ucsf_file: UcsfFile) -> Result<(), Box<dyn std::error::Error + 'static>> {
+    /// // 1H-1H plane of a 3D NOESY-HSQC at 120 ppm in the 15N dimension
+    /// let plane = ucsf_file.plane(&[
+    ///     AxisPosition::Free,
+    ///     AxisPosition::Ppm(120.0),
+    ///     AxisPosition::Free,
+    /// ])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn plane(&self, axes: &[AxisPosition]) -> Result<UcsfFile, UcsfError> {
+        let free_axes = axes
+            .iter()
+            .filter(|position| **position == AxisPosition::Free)
+            .count();
+        if free_axes != 2 {
+            return Err(UcsfError::FreeAxesMismatch {
+                expected: 2,
+                actual: free_axes,
+            });
+        }
+
+        self.slice(axes)
+    }
+
+    /// Extract the slice of the spectrum along all [`AxisPosition::Free`] axes.
+    fn slice(&self, axes: &[AxisPosition]) -> Result<UcsfFile, UcsfError> {
+        if axes.len() != self.axis_headers.len() {
+            return Err(UcsfError::DimensionMismatch {
+                expected: self.axis_headers.len(),
+                actual: axes.len(),
+            });
+        }
+
+        // Resolve all positions to indices, with free axes at index 0
+        let mut point = vec![];
+        for (position, axis_header) in axes.iter().zip(&self.axis_headers) {
+            let index = match position {
+                AxisPosition::Free => 0,
+                AxisPosition::Index(index) => *index,
+                AxisPosition::Ppm(ppm) => {
+                    let index = axis_header.ppm_to_index(*ppm).round();
+                    if index < 0.0 {
+                        return Err(UcsfError::OutOfBounds);
+                    }
+                    index as usize
+                }
+            };
+            point.push(index);
+        }
+        self.check_point(&point)?;
+
+        let free_axes: Vec<_> = axes
+            .iter()
+            .enumerate()
+            .filter(|(_, position)| **position == AxisPosition::Free)
+            .map(|(axis, _)| axis)
+            .collect();
+        let axis_headers: Vec<_> = free_axes
+            .iter()
+            .map(|axis| self.axis_headers[*axis].clone())
+            .collect();
+
+        // The fixed indices of `point` stay in place, only the free ones are updated per point
+        let layout = TileLayout::new(&self.axis_headers);
+        let mut source_indices = point;
+        let mut source_position = |indices: &[usize]| {
+            for (axis, index) in free_axes.iter().zip(indices) {
+                source_indices[*axis] = *index;
+            }
+            layout.position(&source_indices)
+        };
+        let data = tiled_data_from_fn(&axis_headers, |indices| self.data[source_position(indices)]);
+        let imaginary_data = self.imaginary_data.as_ref().map(|imaginary_data| {
+            tiled_data_from_fn(&axis_headers, |indices| {
+                imaginary_data[source_position(indices)]
+            })
+        });
+
+        Ok(UcsfFile {
+            header: Header {
+                dimensions: axis_headers.len() as u8,
+                ..self.header.clone()
+            },
+            axis_headers,
+            data,
+            imaginary_data,
+        })
+    }
+}
@@ -0,0 +1,203 @@
+//! Spectrum with a dimensionality that is known at compile time.
+use std::convert::{TryFrom, TryInto};
+
+use crate::{AbsolutePosValIter, AxisHeader, Tile, Tiles, UcsfError, UcsfFile};
+
+/// Spectrum with `D` axes.
+///
+/// Created from a [`UcsfFile`] via a checked conversion, after which positions are `[usize; D]`
+/// and ppm values are `[f64; D]`, so that a position with the wrong number of axes can't be
+/// constructed.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use std::fs;
+/// # use ucsf_nmr::{Spectrum, UcsfFile};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// #   let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+/// #   let (_remaining_bytes, ucsf_file) = UcsfFile::parse(&file_bytes)?;
+/// let spectrum = Spectrum::<2>::try_from(ucsf_file)?;
+/// for tile in spectrum.tiles() {
+///     for ([i_axis_1, i_axis_2], value) in tile.iter_with_abolute_pos() {
+///         format!("({},{}) : {}", i_axis_1, i_axis_2, value);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Spectrum<const D: usize> {
+    file: UcsfFile,
+}
+
+impl<const D: usize> TryFrom<UcsfFile> for Spectrum<D> {
+    type Error = UcsfError;
+
+    /// Fails with [`UcsfError::DimensionMismatch`] if the file doesn't have exactly `D` axes.
+    fn try_from(file: UcsfFile) -> Result<Self, Self::Error> {
+        if file.axis_headers.len() != D {
+            return Err(UcsfError::DimensionMismatch {
+                expected: D,
+                actual: file.axis_headers.len(),
+            });
+        }
+
+        Ok(Self { file })
+    }
+}
+
+impl UcsfFile {
+    /// Convert the file into a [`Spectrum`] with `D` axes.
+    ///
+    /// Fails with [`UcsfError::DimensionMismatch`] if the file doesn't have exactly `D` axes.
+    pub fn into_spectrum<const D: usize>(self) -> Result<Spectrum<D>, UcsfError> {
+        Spectrum::try_from(self)
+    }
+}
+
+impl<const D: usize> From<Spectrum<D>> for UcsfFile {
+    fn from(spectrum: Spectrum<D>) -> Self {
+        spectrum.file
+    }
+}
+
+impl<const D: usize> Spectrum<D> {
+    /// Returns the underlying file.
+    pub fn file(&self) -> &UcsfFile {
+        &self.file
+    }
+
+    /// Consumes the spectrum and returns the underlying file.
+    pub fn into_file(self) -> UcsfFile {
+        self.file
+    }
+
+    /// Returns the headers of all axes.
+    pub fn axis_headers(&self) -> &[AxisHeader; D] {
+        self.file.axis_headers.as_slice().try_into().unwrap()
+    }
+
+    /// Amount of data points along each axis.
+    pub fn axis_sizes(&self) -> [usize; D] {
+        self.file.axis_sizes().as_slice().try_into().unwrap()
+    }
+
+    /// Returns the value of the data point at the multi-dimension-index `indices`.
+    ///
+    /// Returns `None` if an index is out of bounds.
+    pub fn get(&self, indices: [usize; D]) -> Option<f32> {
+        self.file.get(&indices)
+    }
+
+    /// Returns the value of the data point closest to the ppm position `ppms`.
+    ///
+    /// Returns `None` if the position lies outside of the spectrum.
+    pub fn get_ppm(&self, ppms: [f64; D]) -> Option<f32> {
+        self.file.get_ppm(&ppms)
+    }
+
+    /// Convert a ppm position to the multi-dimension-index of the closest data point.
+    ///
+    /// Returns `None` if the position lies outside of the spectrum.
+    pub fn ppm_to_indices(&self, ppms: [f64; D]) -> Option<[usize; D]> {
+        let indices = self.file.ppm_to_indices(&ppms)?;
+        Some(indices.as_slice().try_into().unwrap())
+    }
+
+    /// Convert a multi-dimension-index to its ppm position.
+    pub fn indices_to_ppm(&self, indices: [usize; D]) -> [f64; D] {
+        let mut ppms = [0f64; D];
+        for ((ppm, index), axis) in ppms.iter_mut().zip(&indices).zip(self.axis_headers()) {
+            *ppm = axis.index_to_ppm(*index as f64);
+        }
+        ppms
+    }
+
+    /// Returns an iterator over all tiles in the spectrum.
+    pub fn tiles(&self) -> SpectrumTiles<'_, D> {
+        SpectrumTiles {
+            tiles: self.file.tiles(),
+        }
+    }
+
+    /// Returns the tile with index `tile_n`.
+    ///
+    /// Tiles are numbered in the same order as they are returned by [`Spectrum::tiles`].
+    pub fn tile(&self, tile_n: usize) -> Option<SpectrumTile<'_, D>> {
+        Some(SpectrumTile {
+            tile: self.file.tile(tile_n)?,
+        })
+    }
+}
+
+/// Iterator over all tiles of a [`Spectrum`].
+pub struct SpectrumTiles<'a, const D: usize> {
+    tiles: Tiles<'a>,
+}
+
+impl<'a, const D: usize> Iterator for SpectrumTiles<'a, D> {
+    type Item = SpectrumTile<'a, D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(SpectrumTile {
+            tile: self.tiles.next()?,
+        })
+    }
+}
+
+/// Tile of a [`Spectrum`].
+pub struct SpectrumTile<'a, const D: usize> {
+    tile: Tile<'a>,
+}
+
+impl<'a, const D: usize> SpectrumTile<'a, D> {
+    /// Returns the untyped tile.
+    pub fn tile(&self) -> &Tile<'a> {
+        &self.tile
+    }
+
+    /// Amount of data points along each axis in this tile.
+    pub fn axis_lengths(&self) -> [usize; D] {
+        self.tile.axis_lengths.as_slice().try_into().unwrap()
+    }
+
+    /// Index of first element along each axis (in relation to total axis).
+    pub fn axis_starts(&self) -> [usize; D] {
+        self.tile.axis_starts.as_slice().try_into().unwrap()
+    }
+
+    /// Iterate over the values in a tile with their absolute position in the spectrum.
+    ///
+    /// **No specific order of the values should be assumes, which is why the position is provided
+    /// in the iterator**
+    pub fn iter_with_abolute_pos(&self) -> SpectrumPosValIter<'a, D> {
+        SpectrumPosValIter {
+            iter: self.tile.iter_with_abolute_pos(),
+        }
+    }
+
+    /// Iterate over the imaginary values in a tile with their absolute position in the spectrum.
+    ///
+    /// Returns `None` if the file contains no imaginary data.
+    pub fn iter_imaginary_with_abolute_pos(&self) -> Option<SpectrumPosValIter<'a, D>> {
+        Some(SpectrumPosValIter {
+            iter: self.tile.iter_imaginary_with_abolute_pos()?,
+        })
+    }
+}
+
+/// Iterator over the values of a [`SpectrumTile`] with their absolute position in the spectrum.
+pub struct SpectrumPosValIter<'a, const D: usize> {
+    iter: AbsolutePosValIter<'a>,
+}
+
+impl<'a, const D: usize> Iterator for SpectrumPosValIter<'a, D> {
+    type Item = ([usize; D], f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_with_pos()
+            .map(|(axis_abs, val)| (axis_abs.try_into().unwrap(), val))
+    }
+}
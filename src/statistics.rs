@@ -0,0 +1,259 @@
+//! Summary statistics of the data in a spectrum.
+//!
+//! All statistics only consider the real part of the data. Zero-padding of the tiles, as well as
+//! values that are not finite (NaN or infinite), are excluded.
+use crate::UcsfFile;
+
+/// Amount of histogram bins used by [`UcsfFile::percentile`].
+const PERCENTILE_BINS: usize = 4096;
+
+/// Scale factor between the median absolute deviation and the standard deviation of normally
+/// distributed noise.
+const MAD_TO_STD: f64 = 1.4826;
+
+/// Amount of values up to which [`UcsfFile::select_by`] stops narrowing down the range via
+/// histograms and selects from the collected values instead.
+const SELECT_COLLECT_LIMIT: usize = 1 << 16;
+
+/// Summary statistics of a spectrum, as returned by [`UcsfFile::statistics`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    /// Amount of data points taken into account.
+    pub count: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f64,
+    /// Root mean square of the values.
+    pub rms: f64,
+}
+
+/// Histogram of the values of a spectrum, as returned by [`UcsfFile::histogram`].
+///
+/// The bins are of equal width and cover the range from `min` to `max` (both inclusive).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub min: f32,
+    pub max: f32,
+    /// Amount of values in each bin.
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Width of a single bin.
+    pub fn bin_width(&self) -> f64 {
+        (self.max as f64 - self.min as f64) / self.counts.len() as f64
+    }
+
+    /// Lower and upper edge of the bin with index `bin`.
+    pub fn bin_edges(&self, bin: usize) -> (f64, f64) {
+        let lower = self.min as f64 + bin as f64 * self.bin_width();
+        (lower, lower + self.bin_width())
+    }
+
+    /// Total amount of values in the histogram.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Approximate the value below which `percentile` percent of the values lie.
+    ///
+    /// `percentile` is clamped to the range `0..=100`. Values are assumed to be evenly
+    /// distributed within a bin, so the accuracy is limited by the bin width.
+    pub fn percentile(&self, percentile: f64) -> f32 {
+        let target = percentile.clamp(0.0, 100.0) / 100.0 * self.total() as f64;
+
+        let mut below = 0;
+        for (bin, count) in self.counts.iter().enumerate() {
+            if *count > 0 && (below + count) as f64 >= target {
+                let (lower, _) = self.bin_edges(bin);
+                let fraction = (target - below as f64) / *count as f64;
+                let value = lower + fraction.max(0.0) * self.bin_width();
+                return (value as f32).max(self.min).min(self.max);
+            }
+            below += count;
+        }
+
+        self.max
+    }
+
+    fn bin(&self, value: f32) -> usize {
+        let bin_width = self.bin_width();
+        if bin_width <= 0.0 {
+            return 0;
+        }
+        let bin = ((value as f64 - self.min as f64) / bin_width) as usize;
+        bin.min(self.counts.len() - 1)
+    }
+}
+
+impl UcsfFile {
+    /// Call `f` with every finite value of the spectrum, excluding padding.
+    fn for_each_value<F: FnMut(f32)>(&self, mut f: F) {
+        for tile in self.tiles() {
+            let mut values = tile.iter_with_abolute_pos();
+            while let Some((_, value)) = values.next_with_pos() {
+                if value.is_finite() {
+                    f(value);
+                }
+            }
+        }
+    }
+
+    /// Calculate minimum, maximum, mean and root mean square of the data in a single pass.
+    ///
+    /// Returns `None` if the spectrum contains no finite values.
+    pub fn statistics(&self) -> Option<Statistics> {
+        let mut count = 0;
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let mut sum = 0f64;
+        let mut sum_squares = 0f64;
+        self.for_each_value(|value| {
+            count += 1;
+            min = min.min(value);
+            max = max.max(value);
+            sum += value as f64;
+            sum_squares += value as f64 * value as f64;
+        });
+
+        if count == 0 {
+            return None;
+        }
+        Some(Statistics {
+            count,
+            min,
+            max,
+            mean: sum / count as f64,
+            rms: (sum_squares / count as f64).sqrt(),
+        })
+    }
+
+    /// Sort the data into `bins` bins of equal width between its minimum and maximum.
+    ///
+    /// Returns `None` if `bins` is 0 or the spectrum contains no finite values.
+    pub fn histogram(&self, bins: usize) -> Option<Histogram> {
+        if bins == 0 {
+            return None;
+        }
+        let statistics = self.statistics()?;
+
+        let mut histogram = Histogram {
+            min: statistics.min,
+            max: statistics.max,
+            counts: vec![0; bins],
+        };
+        self.for_each_value(|value| {
+            let bin = histogram.bin(value);
+            histogram.counts[bin] += 1;
+        });
+
+        Some(histogram)
+    }
+
+    /// Approximate the value below which `percentile` percent of the data lie.
+    ///
+    /// See [`Histogram::percentile`] for the accuracy. To calculate several percentiles, create
+    /// the [`Histogram`] once instead.
+    pub fn percentile(&self, percentile: f64) -> Option<f32> {
+        Some(self.histogram(PERCENTILE_BINS)?.percentile(percentile))
+    }
+
+    /// Estimate the standard deviation of the noise from the median absolute deviation of the
+    /// data.
+    ///
+    /// As the majority of data points in a typical spectrum only contain noise, the estimate is
+    /// robust against the comparatively few data points that are part of peaks.
+    ///
+    /// Returns `None` if the spectrum contains no finite values.
+    pub fn noise_level(&self) -> Option<f32> {
+        let center = self.median_by(|value| value)?;
+        let mad = self.median_by(|value| (value - center).abs())?;
+
+        Some((mad as f64 * MAD_TO_STD) as f32)
+    }
+
+    /// Median of all finite values mapped by `map`, without copying the data.
+    ///
+    /// Returns `None` if the spectrum contains no finite values.
+    fn median_by<F: Fn(f32) -> f32>(&self, map: F) -> Option<f32> {
+        let mut count = 0;
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        self.for_each_value(|value| {
+            let value = map(value);
+            count += 1;
+            min = min.min(value);
+            max = max.max(value);
+        });
+        if count == 0 {
+            return None;
+        }
+
+        let upper = self.select_by(&map, count / 2, count, min, max);
+        match count % 2 == 0 {
+            true => {
+                let lower = self.select_by(&map, count / 2 - 1, count, min, max);
+                Some((lower + upper) / 2.0)
+            }
+            false => Some(upper),
+        }
+    }
+
+    /// Value at `rank` in the sorted values mapped by `map`, of which `count` lie within
+    /// `min..=max`.
+    ///
+    /// The range is narrowed down to a single histogram bin per pass over the data, until few
+    /// enough values are left to collect them.
+    fn select_by<F: Fn(f32) -> f32>(
+        &self,
+        map: &F,
+        mut rank: usize,
+        mut count: usize,
+        mut min: f32,
+        mut max: f32,
+    ) -> f32 {
+        loop {
+            if min == max {
+                return min;
+            }
+            let bin_width = (max as f64 - min as f64) / PERCENTILE_BINS as f64;
+            if count <= SELECT_COLLECT_LIMIT || !bin_width.is_finite() {
+                let mut values = Vec::with_capacity(count);
+                self.for_each_value(|value| {
+                    let value = map(value);
+                    if value >= min && value <= max {
+                        values.push(value);
+                    }
+                });
+                let (_, value, _) = values.select_nth_unstable_by(rank, |a, b| a.total_cmp(b));
+                return *value;
+            }
+
+            // Keep the actual extremes of every bin, so that the next range is as narrow as
+            // possible
+            let mut counts = vec![0; PERCENTILE_BINS];
+            let mut bin_min = vec![f32::INFINITY; PERCENTILE_BINS];
+            let mut bin_max = vec![f32::NEG_INFINITY; PERCENTILE_BINS];
+            self.for_each_value(|value| {
+                let value = map(value);
+                if value >= min && value <= max {
+                    let bin = (((value as f64 - min as f64) / bin_width) as usize)
+                        .min(PERCENTILE_BINS - 1);
+                    counts[bin] += 1;
+                    bin_min[bin] = bin_min[bin].min(value);
+                    bin_max[bin] = bin_max[bin].max(value);
+                }
+            });
+
+            for (bin, bin_count) in counts.into_iter().enumerate() {
+                if rank < bin_count {
+                    count = bin_count;
+                    min = bin_min[bin];
+                    max = bin_max[bin];
+                    break;
+                }
+                rank -= bin_count;
+            }
+        }
+    }
+}
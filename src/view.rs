@@ -0,0 +1,235 @@
+//! Zero-copy access to spectra in borrowed bytes.
+use std::convert::TryInto;
+
+use crate::{
+    decode_data, AbsolutePos, AxisHeader, Header, OwnedTile, TileGeometry, TilePositions,
+    UcsfError, UcsfFile,
+};
+
+/// View of a spectrum that borrows the raw bytes of the file (e.g. from a memory-mapped file).
+///
+/// In contrast to [`UcsfFile`], the data is not copied on parsing, but values are only decoded
+/// when they are accessed.
+///
+/// ```
+/// # use std::fs;
+/// # use ucsf_nmr::UcsfView;
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+/// let file_bytes = fs::read("./tests/data/15n_hsqc.ucsf")?;
+/// let (_remaining_bytes, view) = UcsfView::parse(&file_bytes)?;
+/// for tile in view.tiles() {
+///     for (axis_indices, value) in tile.iter_with_abolute_pos() {
+///         format!("{:?} : {}", axis_indices, value);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UcsfView<'a> {
+    pub header: Header,
+    pub axis_headers: Vec<AxisHeader>,
+    data: &'a [u8],
+}
+
+impl<'a> UcsfView<'a> {
+    pub fn parse(input: &'a [u8]) -> Result<(&'a [u8], Self), UcsfError> {
+        let (rem, header, axis_headers, data) = UcsfFile::parse_raw(input)?;
+
+        Ok((
+            rem,
+            Self {
+                header,
+                axis_headers,
+                data,
+            },
+        ))
+    }
+
+    /// Returns the raw big-endian data in its native tile-layout.
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the amount of data points along `axis`.
+    pub fn axis_data_points(&self, axis: usize) -> u32 {
+        self.axis_headers[axis].data_points
+    }
+
+    /// Returns an iterator over all tiles in the file.
+    pub fn tiles(&self) -> ViewTiles<'a, '_> {
+        ViewTiles {
+            next_index: 0,
+            view: self,
+        }
+    }
+
+    /// Returns the tile with index `tile_n`.
+    ///
+    /// Tiles are numbered in the same order as they are returned by [`UcsfView::tiles`].
+    pub fn tile(&self, tile_n: usize) -> Option<ViewTile<'a>> {
+        if tile_n >= self.axis_tiles().iter().product() {
+            return None;
+        }
+
+        let geometry = TileGeometry::for_tile(&self.axis_headers, tile_n);
+        // * 4 as each data point is a f32
+        let tile_bytes = geometry.data_points() * self.header.components as usize * 4;
+        let data_start = tile_bytes * tile_n;
+
+        Some(ViewTile {
+            axis_lengths: geometry.axis_lengths,
+            axis_starts: geometry.axis_starts,
+            padded_axis_lengths: geometry.padded_axis_lengths,
+            components: self.header.components,
+            bytes: &self.data[data_start..data_start + tile_bytes],
+        })
+    }
+
+    /// Returns the amount of tiles along each axis.
+    pub fn axis_tiles(&self) -> Vec<usize> {
+        self.axis_headers
+            .iter()
+            .map(|axis| axis.num_tiles() as usize)
+            .collect()
+    }
+
+    /// Returns the amount of data points in a tile along all axis.
+    pub fn axis_tile_sizes(&self) -> Vec<usize> {
+        self.axis_headers
+            .iter()
+            .map(|axis| axis.tile_size as usize)
+            .collect()
+    }
+
+    /// Returns the sizes for all axis.
+    pub fn axis_sizes(&self) -> Vec<usize> {
+        self.axis_headers
+            .iter()
+            .map(|axis| axis.data_points as usize)
+            .collect()
+    }
+
+    /// Decode all data into an owned [`UcsfFile`].
+    pub fn to_file(&self) -> UcsfFile {
+        let (data, imaginary_data) = decode_data(self.data, self.header.components);
+
+        UcsfFile {
+            header: self.header.clone(),
+            axis_headers: self.axis_headers.clone(),
+            data,
+            imaginary_data,
+        }
+    }
+}
+
+/// Iterator over all tiles of a [`UcsfView`].
+pub struct ViewTiles<'a, 'v> {
+    next_index: usize,
+    view: &'v UcsfView<'a>,
+}
+
+impl<'a, 'v> Iterator for ViewTiles<'a, 'v> {
+    type Item = ViewTile<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tile = self.view.tile(self.next_index)?;
+        self.next_index += 1;
+        Some(tile)
+    }
+}
+
+/// Tile of a [`UcsfView`], which decodes its values on access.
+pub struct ViewTile<'a> {
+    /// Amount of data points along each axis in this tile.
+    pub axis_lengths: Vec<usize>,
+    /// Index of first element along each axis (in relation to total axis).
+    pub axis_starts: Vec<usize>,
+    /// Amount of data points along each axis in this tile, including zero-padding.
+    pub padded_axis_lengths: Vec<usize>,
+    components: u8,
+    bytes: &'a [u8],
+}
+
+impl<'a> ViewTile<'a> {
+    #[inline]
+    fn decode_at(&self, index: usize) -> f32 {
+        let offset = index * 4;
+        f32::from_be_bytes(self.bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Returns the raw big-endian data of the tile, including zero-padding.
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Decode the value at `index` in the native layout of the tile (including zero-padding).
+    #[inline]
+    pub fn value(&self, index: usize) -> f32 {
+        self.decode_at(index * self.components as usize)
+    }
+
+    /// Decode the imaginary value at `index` in the native layout of the tile (including
+    /// zero-padding).
+    ///
+    /// Returns `None` if the file contains no imaginary data.
+    pub fn imaginary_value(&self, index: usize) -> Option<f32> {
+        match self.components {
+            2 => Some(self.decode_at(index * 2 + 1)),
+            _ => None,
+        }
+    }
+
+    /// Decode all values of the tile into an [`OwnedTile`].
+    pub fn to_owned_tile(&self) -> OwnedTile {
+        let geometry = TileGeometry {
+            axis_lengths: self.axis_lengths.clone(),
+            axis_starts: self.axis_starts.clone(),
+            padded_axis_lengths: self.padded_axis_lengths.clone(),
+        };
+        OwnedTile::decode(geometry, self.bytes, self.components)
+    }
+
+    /// Iterate over the values in a tile with their absolute position in the spectrum.
+    ///
+    /// **No specific order of the values should be assumes, which is why the position is provided
+    /// in the iterator**
+    pub fn iter_with_abolute_pos(&self) -> ViewAbsolutePosValIter<'_> {
+        ViewAbsolutePosValIter {
+            tile: self,
+            positions: TilePositions::new(
+                &self.axis_lengths,
+                &self.axis_starts,
+                &self.padded_axis_lengths,
+            ),
+        }
+    }
+}
+
+/// Iterator over the values of a [`ViewTile`] with their absolute position in the spectrum.
+pub struct ViewAbsolutePosValIter<'a> {
+    tile: &'a ViewTile<'a>,
+    positions: TilePositions,
+}
+
+impl<'a> ViewAbsolutePosValIter<'a> {
+    /// Advance the iterator and return the absolute position and value of the next data point.
+    ///
+    /// In contrast to [`Iterator::next`], the position is borrowed from the iterator.
+    #[inline]
+    pub fn next_with_pos(&mut self) -> Option<(&[usize], f32)> {
+        let index = self.positions.advance()?;
+        Some((self.positions.position(), self.tile.value(index)))
+    }
+}
+
+impl<'a> Iterator for ViewAbsolutePosValIter<'a> {
+    type Item = (AbsolutePos, f32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_pos()
+            .map(|(axis_abs, value)| (AbsolutePos::from_slice(axis_abs), value))
+    }
+}
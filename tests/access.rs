@@ -0,0 +1,49 @@
+use ucsf_nmr::UcsfFile;
+
+#[test]
+fn get_matches_data_continous() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let data = file.data_continous();
+    for i_axis_1 in (0..512).step_by(7) {
+        for i_axis_2 in 0..257 {
+            assert_eq!(
+                file.get(&[i_axis_1, i_axis_2]),
+                Some(data[i_axis_1 * 257 + i_axis_2])
+            );
+        }
+    }
+    assert_eq!(file.get(&[511, 256]), Some(data[511 * 257 + 256]));
+}
+
+#[test]
+fn get_out_of_bounds() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(file.get(&[512, 0]), None);
+    // Within the zero-padding of the last tile
+    assert_eq!(file.get(&[0, 257]), None);
+    assert_eq!(file.get(&[0]), None);
+    assert_eq!(file.get(&[0, 0, 0]), None);
+}
+
+#[test]
+fn get_ppm() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let ppm_1 = file.axis_headers[0].index_to_ppm(100.0);
+    let ppm_2 = file.axis_headers[1].index_to_ppm(200.0);
+    assert_eq!(file.get_ppm(&[ppm_1, ppm_2]), file.get(&[100, 200]));
+
+    // Rounds to the closest data point
+    let ppm_1 = file.axis_headers[0].index_to_ppm(100.4);
+    let ppm_2 = file.axis_headers[1].index_to_ppm(199.6);
+    assert_eq!(file.get_ppm(&[ppm_1, ppm_2]), file.get(&[100, 200]));
+
+    assert_eq!(file.get_ppm(&[200.0, 8.0]), None);
+    assert_eq!(file.get_ppm(&[117.0, -20.0]), None);
+    assert_eq!(file.get_ppm(&[117.0]), None);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::{hsqc, synthetic_file};
+use ucsf_nmr::{UcsfError, UcsfFile};
+
+#[test]
+fn add_and_subtract() {
+    let file = hsqc();
+    let doubled = file.add(&file).unwrap();
+    assert_eq!(doubled.data_continous(), file.scale(2.0).data_continous());
+
+    let difference = doubled.subtract(&file).unwrap();
+    assert_eq!(difference.data_continous(), file.data_continous());
+    assert_eq!(difference.axis_headers, file.axis_headers);
+
+    let zero = file.subtract(&file).unwrap();
+    assert!(zero.data_continous().iter().all(|value| *value == 0.0));
+}
+
+#[test]
+fn weighted_sum() {
+    let file = hsqc();
+    let scaled = file.scale(3.0);
+    let sum = UcsfFile::weighted_sum(&[(&file, 0.5), (&scaled, 0.5)]).unwrap();
+    assert_eq!(sum.data_continous(), file.scale(2.0).data_continous());
+
+    assert_eq!(
+        UcsfFile::weighted_sum(&[]).unwrap_err(),
+        UcsfError::NoSpectra
+    );
+}
+
+#[test]
+fn different_tile_layouts() {
+    let a = synthetic_file(&[9, 13], &[4, 6]);
+    let b = synthetic_file(&[9, 13], &[3, 5]);
+    let sum = a.add(&b).unwrap();
+    assert_eq!(sum.axis_tile_sizes(), vec![4, 6]);
+    let expected: Vec<_> = a.data_continous().iter().map(|value| 2.0 * value).collect();
+    assert_eq!(sum.data_continous(), expected);
+}
+
+#[test]
+fn imaginary_data() {
+    let mut a = synthetic_file(&[8, 12], &[4, 6]);
+    a.header.components = 2;
+    a.imaginary_data = Some(a.data.iter().map(|value| -value).collect());
+    let b = synthetic_file(&[8, 12], &[4, 6]);
+
+    let sum = a.add(&a).unwrap();
+    assert_eq!(sum.header.components, 2);
+    assert_eq!(
+        sum.imaginary_data_continous().unwrap(),
+        a.scale(2.0).imaginary_data_continous().unwrap()
+    );
+
+    let mixed = a.add(&b).unwrap();
+    assert_eq!(mixed.header.components, 1);
+    assert_eq!(mixed.imaginary_data, None);
+}
+
+#[test]
+fn incompatible_axes() {
+    let file = hsqc();
+    let mismatch = |modify: &dyn Fn(&mut UcsfFile)| {
+        let mut other = file.clone();
+        modify(&mut other);
+        file.subtract(&other).unwrap_err()
+    };
+
+    assert_eq!(
+        mismatch(&|other| other.axis_headers[0].nucleus_name = "13C".to_owned()),
+        UcsfError::IncompatibleAxis {
+            axis: 0,
+            property: "nucleus"
+        }
+    );
+    assert_eq!(
+        mismatch(&|other| other.axis_headers[1].data_points -= 1),
+        UcsfError::IncompatibleAxis {
+            axis: 1,
+            property: "data points"
+        }
+    );
+    assert_eq!(
+        mismatch(&|other| other.axis_headers[0].frequency *= 1.01),
+        UcsfError::IncompatibleAxis {
+            axis: 0,
+            property: "spectrometer frequency"
+        }
+    );
+    assert_eq!(
+        mismatch(&|other| other.axis_headers[1].spectral_width *= 1.01),
+        UcsfError::IncompatibleAxis {
+            axis: 1,
+            property: "spectral width"
+        }
+    );
+    assert_eq!(
+        mismatch(&|other| other.axis_headers[0].center += 0.5),
+        UcsfError::IncompatibleAxis {
+            axis: 0,
+            property: "center"
+        }
+    );
+    assert_eq!(
+        file.add(&synthetic_file(&[4, 6, 8], &[2, 3, 4]))
+            .unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 3
+        }
+    );
+
+    // Tiny differences, e.g. from rounding, are tolerated
+    let mut other = file.clone();
+    other.axis_headers[0].frequency *= 1.000_001;
+    other.axis_headers[1].spectral_width *= 1.000_001;
+    other.axis_headers[1].center += 1e-6;
+    assert!(file.check_compatible(&other).is_ok());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::continous_position;
+use ucsf_nmr::{AxisHeader, UcsfError, UcsfFile};
+
+#[test]
+fn build_inverse_of_data_continous() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    let data = file.data_continous();
+    assert_eq!(data.len(), 512 * 257);
+    let built = file
+        .axis_headers
+        .iter()
+        .fold(UcsfFile::builder(data), |builder, axis_header| {
+            builder.axis(axis_header.clone())
+        })
+        .tile_sizes(&file.axis_tile_sizes())
+        .build()
+        .expect("Failed building");
+
+    assert_eq!(built.axis_headers, file.axis_headers);
+    assert_eq!(built.header.dimensions, 2);
+    assert_eq!(built.header.components, 1);
+    // Includes the zero-padding of the edge tiles
+    assert_eq!(built.data, file.data);
+}
+
+#[test]
+fn build_default_tile_sizes() {
+    let sizes = [40, 300, 7];
+    let data: Vec<_> = (0..40 * 300 * 7).map(|value| value as f32).collect();
+    let file = UcsfFile::builder(data)
+        .axis(AxisHeader::new("1H", 40, 600.0, 6000.0, 4.7))
+        .axis(AxisHeader::new("15N", 300, 60.8, 2000.0, 118.0))
+        .axis(AxisHeader::new("13C", 7, 150.9, 9000.0, 40.0))
+        .build()
+        .expect("Failed building");
+
+    assert_eq!(file.axis_tile_sizes(), vec![20, 38, 7]);
+    assert!(file.axis_tile_sizes().iter().product::<usize>() <= 8192);
+    assert_eq!(file.axis_headers[1].nucleus_name, "15N");
+    for indices in [[0, 0, 0], [39, 299, 6], [21, 38, 3], [10, 150, 5]].iter() {
+        assert_eq!(
+            file.get(indices),
+            Some(continous_position(&sizes, indices) as f32)
+        );
+    }
+
+    let bytes = file.to_bytes();
+    let (rem, parsed) = UcsfFile::parse(&bytes).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.data_continous(), file.data_continous());
+}
+
+#[test]
+fn build_complex() {
+    let data: Vec<_> = (0..20).map(|value| value as f32).collect();
+    let imaginary: Vec<_> = data.iter().map(|value| -value).collect();
+    let file = UcsfFile::builder(data.clone())
+        .imaginary_data(imaginary.clone())
+        .axis(AxisHeader::new("1H", 4, 600.0, 6000.0, 4.7))
+        .axis(AxisHeader::new("1H", 5, 600.0, 6000.0, 4.7))
+        .tile_sizes(&[3, 2])
+        .build()
+        .expect("Failed building");
+
+    assert_eq!(file.header.components, 2);
+    assert_eq!(file.data_continous(), data);
+    assert_eq!(file.imaginary_data_continous(), Some(imaginary));
+}
+
+#[test]
+fn build_errors() {
+    let axis = || AxisHeader::new("1H", 4, 600.0, 6000.0, 4.7);
+
+    assert_eq!(
+        UcsfFile::builder(vec![0.0; 4]).build().unwrap_err(),
+        UcsfError::NoAxes
+    );
+    assert_eq!(
+        UcsfFile::builder(vec![0.0; 15])
+            .axis(axis())
+            .axis(axis())
+            .build()
+            .unwrap_err(),
+        UcsfError::DataSizeMismatch {
+            expected: 16,
+            actual: 15
+        }
+    );
+    assert_eq!(
+        UcsfFile::builder(vec![0.0; 16])
+            .imaginary_data(vec![0.0; 4])
+            .axis(axis())
+            .axis(axis())
+            .build()
+            .unwrap_err(),
+        UcsfError::DataSizeMismatch {
+            expected: 16,
+            actual: 4
+        }
+    );
+    assert_eq!(
+        UcsfFile::builder(vec![0.0; 16])
+            .axis(axis())
+            .axis(axis())
+            .tile_sizes(&[2])
+            .build()
+            .unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        UcsfFile::builder(vec![0.0; 16])
+            .axis(axis())
+            .axis(axis())
+            .tile_sizes(&[2, 0])
+            .build()
+            .unwrap_err(),
+        UcsfError::InvalidAxisSize(1)
+    );
+}
@@ -0,0 +1,148 @@
+//! Helpers shared between the integration tests.
+#![allow(dead_code)]
+
+use ucsf_nmr::{AxisHeader, Header, UcsfFile};
+
+/// The 15N HSQC test spectrum with 256 x 352 data points in 2 x 2 tiles of 128 x 176, without
+/// padding.
+pub fn hsqc() -> UcsfFile {
+    let contents = include_bytes!("../data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    file
+}
+
+/// The high resolution 15N HSQC test spectrum with 512 x 257 data points in 4 x 5 tiles of
+/// 128 x 64, where the last tiles along the second axis are padded.
+pub fn highres_hsqc() -> UcsfFile {
+    let contents = include_bytes!("../data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    file
+}
+
+/// 15N and 1H axes centered at 118 and 8 ppm with the given data points and spectral widths.
+pub fn hsqc_axes(data_points: [u32; 2], spectral_widths: [f32; 2]) -> [AxisHeader; 2] {
+    [
+        AxisHeader::new("15N", data_points[0], 60.0, spectral_widths[0], 118.0),
+        AxisHeader::new("1H", data_points[1], 600.0, spectral_widths[1], 8.0),
+    ]
+}
+
+/// Build a 2D spectrum with the given axes and tile sizes, where the value of each data point is
+/// given by `value` at its indices.
+pub fn spectrum_2d(
+    axes: &[AxisHeader; 2],
+    tile_sizes: [usize; 2],
+    value: impl Fn(f64, f64) -> f64,
+) -> UcsfFile {
+    let sizes = [axes[0].data_points as usize, axes[1].data_points as usize];
+    let data = (0..sizes[0] * sizes[1])
+        .map(|pos| value((pos / sizes[1]) as f64, (pos % sizes[1]) as f64) as f32)
+        .collect();
+
+    UcsfFile::builder(data)
+        .axis(axes[0].clone())
+        .axis(axes[1].clone())
+        .tile_sizes(&tile_sizes)
+        .build()
+        .unwrap()
+}
+
+/// Gaussian with a height of 1 and the full width at half maximum `width`.
+pub fn gaussian(offset: f64, width: f64) -> f64 {
+    (-4.0 * std::f64::consts::LN_2 * offset * offset / (width * width)).exp()
+}
+
+/// Build a 2D spectrum with the given axes and tile sizes, which contains gaussian peaks at
+/// fractional indices, given as (centers, widths, height) with the widths in data points.
+pub fn gaussian_spectrum(
+    axes: &[AxisHeader; 2],
+    tile_sizes: [usize; 2],
+    peaks: &[([f64; 2], [f64; 2], f64)],
+) -> UcsfFile {
+    spectrum_2d(axes, tile_sizes, |x, y| {
+        peaks
+            .iter()
+            .map(|(centers, widths, height)| {
+                height * gaussian(x - centers[0], widths[0]) * gaussian(y - centers[1], widths[1])
+            })
+            .sum()
+    })
+}
+
+/// Row-major position of `indices` in an array with dimensions `sizes`.
+pub fn continous_position(sizes: &[usize], indices: &[usize]) -> usize {
+    sizes
+        .iter()
+        .zip(indices)
+        .fold(0, |pos, (size, index)| pos * size + index)
+}
+
+/// Row-major indices of `pos` in an array with dimensions `sizes`.
+pub fn continous_indices(sizes: &[usize], mut pos: usize) -> Vec<usize> {
+    let mut indices = vec![0; sizes.len()];
+    for (index, size) in indices.iter_mut().zip(sizes).rev() {
+        *index = pos % size;
+        pos /= size;
+    }
+    indices
+}
+
+/// Build a synthetic spectrum, where the value of each data point is its position in
+/// the continous layout.
+///
+/// The padding of edge tiles is filled with `-1.0` instead of zeros, so that it can be detected.
+pub fn synthetic_file(axis_sizes: &[usize], tile_sizes: &[usize]) -> UcsfFile {
+    let header = Header {
+        dimensions: axis_sizes.len() as u8,
+        components: 1,
+        format_version: 2,
+        remainder: vec![0; 166],
+    };
+    let axis_headers: Vec<_> = axis_sizes
+        .iter()
+        .zip(tile_sizes)
+        .enumerate()
+        .map(|(axis, (size, tile_size))| AxisHeader {
+            nucleus_name: "1H".to_owned(),
+            data_points: *size as u32,
+            unknown: (*size as u32).to_be_bytes(),
+            tile_size: *tile_size as u32,
+            frequency: 600.0,
+            spectral_width: 6000.0,
+            center: 4.7 + axis as f32,
+            remainder: vec![0; 96],
+        })
+        .collect();
+
+    let axis_tiles: Vec<_> = axis_sizes
+        .iter()
+        .zip(tile_sizes)
+        .map(|(size, tile_size)| size.div_ceil(*tile_size))
+        .collect();
+    let mut data = vec![];
+    for tile_n in 0..axis_tiles.iter().product() {
+        let tile_indices = continous_indices(&axis_tiles, tile_n);
+        for point_n in 0..tile_sizes.iter().product() {
+            let abs: Vec<_> = continous_indices(tile_sizes, point_n)
+                .iter()
+                .zip(&tile_indices)
+                .zip(tile_sizes)
+                .map(|((rel, tile_index), tile_size)| tile_index * tile_size + rel)
+                .collect();
+            let is_padding = abs.iter().zip(axis_sizes).any(|(abs, size)| abs >= size);
+            match is_padding {
+                true => data.push(-1.0),
+                false => data.push(continous_position(axis_sizes, &abs) as f32),
+            }
+        }
+    }
+
+    UcsfFile {
+        header,
+        axis_headers,
+        data,
+        imaginary_data: None,
+    }
+}
@@ -0,0 +1,88 @@
+use std::convert::TryInto;
+
+use ucsf_nmr::{Header, UcsfError, UcsfFile};
+
+fn complex_file() -> UcsfFile {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, mut file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| -2.0 * value).collect());
+    file
+}
+
+#[test]
+fn parse_complex_file() {
+    let file = complex_file();
+    let contents = file.to_bytes();
+
+    let (rem, parsed) = UcsfFile::parse(&contents).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.header.components, 2);
+    assert_eq!(parsed.data, file.data);
+    assert_eq!(parsed.imaginary_data, file.imaginary_data);
+}
+
+#[test]
+fn complex_data_interleaved() {
+    let file = complex_file();
+    let contents = file.to_bytes();
+    assert_eq!(contents.len(), 180 + 2 * 128 + 2 * 4 * file.data.len());
+
+    let raw_value = |n: usize| {
+        let offset = 180 + 2 * 128 + 4 * n;
+        f32::from_be_bytes(contents[offset..offset + 4].try_into().unwrap())
+    };
+    for n in [0, 1, 1000, file.data.len() - 1].iter() {
+        assert_eq!(raw_value(2 * n), file.data[*n]);
+        assert_eq!(
+            raw_value(2 * n + 1),
+            file.imaginary_data.as_ref().unwrap()[*n]
+        );
+    }
+}
+
+#[test]
+fn complex_tiles() {
+    let file = complex_file();
+    let (_, file) = UcsfFile::parse(&file.to_bytes()).expect("Failed parsing");
+
+    for tile in file.tiles() {
+        assert_eq!(tile.data().len(), tile.imaginary_data().unwrap().len());
+        let real = tile.iter_with_abolute_pos();
+        let imaginary = tile.iter_imaginary_with_abolute_pos().unwrap();
+        for ((real_pos, real), (imaginary_pos, imaginary)) in real.zip(imaginary) {
+            assert_eq!(real_pos, imaginary_pos);
+            assert_eq!(imaginary, -2.0 * real);
+        }
+    }
+
+    let real = file.data_continous();
+    let imaginary = file.imaginary_data_continous().unwrap();
+    for (real, imaginary) in real.iter().zip(&imaginary) {
+        assert_eq!(*imaginary, -2.0 * real);
+    }
+}
+
+#[test]
+fn real_file_has_no_imaginary_data() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(file.imaginary_data, None);
+    assert_eq!(file.imaginary_data_continous(), None);
+    let tile = file.tiles().next().unwrap();
+    assert!(tile.imaginary_data().is_none());
+    assert!(tile.iter_imaginary_with_abolute_pos().is_none());
+}
+
+#[test]
+fn parse_header_components_error() {
+    let mut contents = include_bytes!("./data/15n_hsqc.ucsf").to_vec();
+    contents[11] = 3;
+
+    assert_eq!(
+        Err(UcsfError::UnsupportedComponents),
+        Header::parse(&contents[..])
+    );
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::{gaussian_spectrum, hsqc, hsqc_axes, synthetic_file};
+use float_eq::assert_float_eq;
+use ucsf_nmr::{AxisPosition, ContourLevels, UcsfError, UcsfFile};
+
+/// 2D spectrum with 8x8 tiles and a round gaussian peak of the given height at `center`.
+fn spectrum_with_peak(center: (f64, f64), height: f64) -> UcsfFile {
+    // Full width at half maximum of sqrt(200 ln 2), i.e. exp(-distance^2 / 50)
+    let width = (200.0 * std::f64::consts::LN_2).sqrt();
+    gaussian_spectrum(
+        &hsqc_axes([40, 40], [1200.0, 2400.0]),
+        [8, 8],
+        &[([center.0, center.1], [width, width], height)],
+    )
+}
+
+fn to_indices(file: &UcsfFile, (ppm_1, ppm_2): (f64, f64)) -> (f64, f64) {
+    (
+        file.axis_headers[0].ppm_to_index(ppm_1),
+        file.axis_headers[1].ppm_to_index(ppm_2),
+    )
+}
+
+#[test]
+fn closed_contour_across_tiles() {
+    // The peak spans multiple tiles in both directions
+    let file = spectrum_with_peak((20.0, 20.0), 100.0);
+    let contours = file.contours(&[50.0]).unwrap();
+
+    assert_eq!(contours.len(), 1);
+    assert!(contours[0].closed);
+    assert_eq!(contours[0].level, 50.0);
+    // The radius of the contour at half height is sqrt(50 * ln(2))
+    let radius = (50.0 * std::f64::consts::LN_2).sqrt();
+    for point in &contours[0].points {
+        let (x, y) = to_indices(&file, *point);
+        let distance = ((x - 20.0).powi(2) + (y - 20.0).powi(2)).sqrt();
+        assert_float_eq!(distance, radius, abs <= 0.1);
+    }
+}
+
+#[test]
+fn open_contour_at_border() {
+    let file = spectrum_with_peak((0.0, 20.0), 100.0);
+    let contours = file.contours(&[50.0]).unwrap();
+
+    assert_eq!(contours.len(), 1);
+    assert!(!contours[0].closed);
+    let first = to_indices(&file, contours[0].points[0]);
+    let last = to_indices(&file, *contours[0].points.last().unwrap());
+    assert_float_eq!(first.0, 0.0, abs <= 1e-9);
+    assert_float_eq!(last.0, 0.0, abs <= 1e-9);
+}
+
+#[test]
+fn negative_levels() {
+    let file = spectrum_with_peak((20.0, 20.0), -100.0);
+    assert_eq!(file.contours(&[50.0]).unwrap().len(), 0);
+
+    let contours = file.contours(&[-25.0, -50.0, -75.0]).unwrap();
+    assert_eq!(contours.len(), 3);
+    assert!(contours.iter().all(|contour| contour.closed));
+    // Lower levels are further inside the peak
+    assert!(contours[0].points.len() > contours[2].points.len());
+}
+
+#[test]
+fn contours_of_plane() {
+    let file = synthetic_file(&[4, 6, 8], &[2, 3, 4]);
+    assert_eq!(
+        file.contours(&[1.0]).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 3
+        }
+    );
+
+    let plane = file
+        .plane(&[
+            AxisPosition::Index(1),
+            AxisPosition::Free,
+            AxisPosition::Free,
+        ])
+        .unwrap();
+    // Values increase monotonically, so each level is a single open line
+    let contours = plane.contours(&[60.5, 80.5]).unwrap();
+    assert_eq!(contours.len(), 2);
+    assert!(contours.iter().all(|contour| !contour.closed));
+}
+
+#[test]
+fn contour_level_series() {
+    let levels = ContourLevels::up_to(10.0, 100.0, 2.0).unwrap();
+    assert_eq!(levels.count, 4);
+    assert_eq!(levels.levels(), vec![10.0, 20.0, 40.0, 80.0]);
+
+    let negative = ContourLevels::up_to(-10.0, -35.0, 1.5).unwrap();
+    assert_eq!(negative.levels(), vec![-10.0, -15.0, -22.5, -33.75]);
+    // Highest level exactly at the bound
+    assert_eq!(ContourLevels::up_to(1.0, 8.0, 2.0).unwrap().count, 4);
+
+    assert_eq!(ContourLevels::up_to(10.0, 5.0, 2.0), None);
+    assert_eq!(ContourLevels::up_to(-10.0, 50.0, 2.0), None);
+    assert_eq!(ContourLevels::up_to(10.0, 50.0, 1.0), None);
+}
+
+#[test]
+fn auto_contour_levels() {
+    let file = hsqc();
+    let noise = file.noise_level().unwrap();
+    let (min, max) = file.bounds();
+    let levels = file.auto_contour_levels();
+
+    let positive = levels.positive.unwrap();
+    assert_float_eq!(positive.lowest, 5.0 * noise, rel <= 1e-6);
+    assert_float_eq!(positive.factor, 1.4, rel <= 1e-6);
+    let highest = *positive.levels().last().unwrap();
+    assert!(highest <= max);
+    assert!(highest * 1.4 > max);
+    if let Some(negative) = levels.negative {
+        assert_float_eq!(negative.lowest, -5.0 * noise, rel <= 1e-6);
+        assert!(*negative.levels().last().unwrap() >= min);
+    }
+    assert!(levels.levels().len() >= positive.count);
+
+    let custom = file.contour_levels(10.0, 2.0);
+    assert_float_eq!(custom.positive.unwrap().lowest, 10.0 * noise, rel <= 1e-6);
+}
+
+#[test]
+fn auto_contour_levels_of_peak() {
+    let file = spectrum_with_peak((20.0, 20.0), -100.0);
+    let levels = file.auto_contour_levels();
+    // Most data points are close to zero, so only the peak exceeds the noise
+    assert_eq!(levels.positive, None);
+    let negative = levels.negative.unwrap();
+    assert!(negative.lowest < 0.0);
+    for contour in file.contours(&levels.levels()).unwrap() {
+        assert!(contour.closed);
+    }
+}
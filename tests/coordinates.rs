@@ -0,0 +1,86 @@
+mod common;
+
+use float_eq::assert_float_eq;
+
+use common::hsqc;
+
+// Expected values are the ppm scale as displayed by Sparky for 15n_hsqc.ucsf
+// (15N: 132.042 - 102.162 ppm, 1H: 10.998 - 5.507 ppm).
+
+#[test]
+fn index_to_ppm() {
+    let file = hsqc();
+    let n = &file.axis_headers[0];
+    let h = &file.axis_headers[1];
+
+    assert_float_eq!(n.index_to_ppm(0.0), 132.041578, abs <= 1e-5);
+    assert_float_eq!(n.index_to_ppm(128.0), 117.042992, abs <= 1e-5);
+    assert_float_eq!(n.index_to_ppm(255.0), 102.161582, abs <= 1e-5);
+
+    assert_float_eq!(h.index_to_ppm(0.0), 10.997707, abs <= 1e-5);
+    assert_float_eq!(h.index_to_ppm(176.0), 8.244598, abs <= 1e-5);
+    assert_float_eq!(h.index_to_ppm(351.0), 5.507133, abs <= 1e-5);
+}
+
+#[test]
+fn ppm_to_index() {
+    let file = hsqc();
+    let n = &file.axis_headers[0];
+    let h = &file.axis_headers[1];
+
+    assert_float_eq!(n.ppm_to_index(132.041578), 0.0, abs <= 1e-4);
+    assert_float_eq!(n.ppm_to_index(117.042992), 128.0, abs <= 1e-4);
+    assert_float_eq!(h.ppm_to_index(5.507133), 351.0, abs <= 1e-4);
+
+    for index in [0.0, 0.5, 17.25, 200.0, 351.0].iter() {
+        assert_float_eq!(h.ppm_to_index(h.index_to_ppm(*index)), *index, abs <= 1e-9);
+    }
+}
+
+#[test]
+fn index_to_hz() {
+    let file = hsqc();
+    let n = &file.axis_headers[0];
+
+    assert_float_eq!(n.index_to_hz(0.0), 8032.485328, abs <= 1e-3);
+    assert_float_eq!(
+        n.index_to_hz(0.0) - n.index_to_hz(1.0),
+        n.hz_per_point(),
+        abs <= 1e-6
+    );
+    assert_float_eq!(n.hz_to_index(n.index_to_hz(42.5)), 42.5, abs <= 1e-9);
+}
+
+#[test]
+fn ppm_range() {
+    let file = hsqc();
+    let n = &file.axis_headers[0];
+
+    let (min, max) = n.ppm_range();
+    assert_float_eq!(min, 102.161582, abs <= 1e-5);
+    assert_float_eq!(max, 132.041578, abs <= 1e-5);
+    assert_float_eq!(n.spectral_width_ppm(), 29.997174, abs <= 1e-5);
+
+    let scale = n.ppm_scale();
+    assert_eq!(scale.len(), 256);
+    assert_float_eq!(scale[0], max, abs <= 1e-9);
+    assert_float_eq!(scale[255], min, abs <= 1e-9);
+}
+
+#[test]
+fn ppm_to_index_range() {
+    let file = hsqc();
+    let h = &file.axis_headers[1];
+
+    assert_eq!(h.ppm_to_index_range(20.0, 0.0), 0..352);
+    assert_eq!(h.ppm_to_index_range(0.0, 20.0), 0..352);
+    let center = h.index_to_ppm(176.0);
+    assert_eq!(h.ppm_to_index_range(center, center), 176..177);
+    assert_eq!(h.ppm_to_index_range(8.2, 8.3), 173..179);
+    // Between the data points 175 and 176
+    let between = h.index_to_ppm(175.5);
+    assert_eq!(h.ppm_to_index_range(between, between), 176..176);
+    assert_eq!(h.ppm_to_index_range(h.index_to_ppm(10.0), 0.0), 10..352);
+    assert_eq!(h.ppm_to_index_range(30.0, 20.0).len(), 0);
+    assert_eq!(h.ppm_to_index_range(-30.0, -20.0).len(), 0);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::{continous_position, synthetic_file};
+use ucsf_nmr::UcsfFile;
+
+fn check_file(axis_sizes: &[usize], tile_sizes: &[usize]) {
+    let contents = synthetic_file(axis_sizes, tile_sizes).to_bytes();
+    let (rem, file) = UcsfFile::parse(&contents).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(file.axis_sizes(), axis_sizes);
+
+    let expected_tiles: usize = axis_sizes
+        .iter()
+        .zip(tile_sizes)
+        .map(|(size, tile_size)| size.div_ceil(*tile_size))
+        .product();
+    assert_eq!(file.tiles().count(), expected_tiles);
+    for (tile_n, tile) in file.tiles().enumerate() {
+        let by_index = file.tile(tile_n).unwrap();
+        assert_eq!(tile.axis_starts, by_index.axis_starts);
+        assert_eq!(tile.axis_lengths, by_index.axis_lengths);
+        assert_eq!(tile.data(), by_index.data());
+    }
+    assert!(file.tile(expected_tiles).is_none());
+
+    let mut seen = vec![false; axis_sizes.iter().product()];
+    for tile in file.tiles() {
+        for (pos, value) in tile.iter_with_abolute_pos() {
+            let continous_pos = continous_position(axis_sizes, &pos);
+            assert_eq!(value, continous_pos as f32);
+            assert!(!seen[continous_pos]);
+            seen[continous_pos] = true;
+        }
+    }
+    assert!(seen.iter().all(|seen| *seen));
+
+    let data = file.data_continous();
+    assert_eq!(data.len(), axis_sizes.iter().product::<usize>());
+    for (pos, value) in data.iter().enumerate() {
+        assert_eq!(*value, pos as f32);
+    }
+}
+
+#[test]
+fn synthetic_1d() {
+    check_file(&[32], &[8]);
+}
+
+#[test]
+fn synthetic_2d() {
+    check_file(&[8, 12], &[4, 6]);
+}
+
+#[test]
+fn synthetic_3d() {
+    check_file(&[4, 6, 8], &[2, 3, 4]);
+}
+
+#[test]
+fn synthetic_4d() {
+    check_file(&[4, 6, 4, 6], &[2, 3, 2, 2]);
+}
+
+#[test]
+fn synthetic_4d_as_4d() {
+    let contents = synthetic_file(&[2, 4, 2, 4], &[1, 2, 2, 2]).to_bytes();
+    let (_, file) = UcsfFile::parse(&contents).expect("Failed parsing");
+
+    let tile = file.tiles().nth(1).unwrap();
+    let mut iter = tile.iter_with_abolute_pos();
+    let ((i1, i2, i3, i4), value) = iter.as_4d().next().unwrap();
+    assert_eq!((i1, i2, i3, i4), (0, 0, 0, 2));
+    assert_eq!(value, 2.0);
+}
+
+#[test]
+fn synthetic_5d() {
+    check_file(&[2, 4, 2, 4, 6], &[1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn synthetic_9d() {
+    // More axes than are stored inline in positions
+    check_file(&[2, 3, 2, 2, 2, 2, 2, 2, 3], &[1, 2, 2, 1, 2, 2, 1, 2, 2]);
+}
+
+#[test]
+fn synthetic_1d_padded() {
+    check_file(&[30], &[8]);
+}
+
+#[test]
+fn synthetic_2d_padded() {
+    check_file(&[9, 13], &[4, 6]);
+}
+
+#[test]
+fn synthetic_3d_padded() {
+    check_file(&[5, 6, 7], &[2, 3, 4]);
+}
+
+#[test]
+fn synthetic_4d_padded() {
+    check_file(&[3, 7, 4, 5], &[2, 3, 2, 2]);
+}
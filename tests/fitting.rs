@@ -0,0 +1,177 @@
+mod common;
+
+use common::{gaussian, gaussian_spectrum, hsqc, hsqc_axes, spectrum_2d};
+use float_eq::assert_float_eq;
+use std::f64::consts::{LN_2, PI};
+use ucsf_nmr::{FittedPeak, Lineshape, PeakFitter, PeakPicker, Threshold, UcsfError, UcsfFile};
+
+fn lorentzian(offset: f64, width: f64) -> f64 {
+    1.0 / (1.0 + 4.0 * offset * offset / (width * width))
+}
+
+/// 2D spectrum with peaks at fractional indices, given as (centers, widths, height).
+fn spectrum<F: Fn(f64, f64) -> f64>(shape: F, peaks: &[([f64; 2], [f64; 2], f64)]) -> UcsfFile {
+    spectrum_2d(&hsqc_axes([48, 64], [1200.0, 3200.0]), [16, 16], |x, y| {
+        peaks
+            .iter()
+            .map(|(centers, widths, height)| {
+                height * shape(x - centers[0], widths[0]) * shape(y - centers[1], widths[1])
+            })
+            .sum()
+    })
+}
+
+fn ppms(file: &UcsfFile, indices: [f64; 2]) -> Vec<f64> {
+    vec![
+        file.axis_headers[0].index_to_ppm(indices[0]),
+        file.axis_headers[1].index_to_ppm(indices[1]),
+    ]
+}
+
+fn assert_peak(
+    file: &UcsfFile,
+    peak: &FittedPeak,
+    centers: [f64; 2],
+    widths: [f64; 2],
+    height: f64,
+) {
+    let expected_ppms = ppms(file, centers);
+    assert_float_eq!(peak.ppms[0], expected_ppms[0], abs <= 1e-3);
+    assert_float_eq!(peak.ppms[1], expected_ppms[1], abs <= 1e-4);
+    // 25 Hz and 50 Hz per data point
+    assert_float_eq!(peak.linewidths_hz[0], widths[0] * 25.0, rel <= 1e-3);
+    assert_float_eq!(peak.linewidths_hz[1], widths[1] * 50.0, rel <= 1e-3);
+    assert_float_eq!(peak.height, height, rel <= 1e-3);
+}
+
+#[test]
+fn gaussian_single() {
+    let file = gaussian_spectrum(
+        &hsqc_axes([48, 64], [1200.0, 3200.0]),
+        [16, 16],
+        &[([20.3, 30.6], [3.0, 4.0], 1000.0)],
+    );
+    let fitter = PeakFitter::new(Lineshape::Gaussian);
+    let peaks = file
+        .fit_peaks(&[ppms(&file, [20.0, 31.0])], &fitter)
+        .unwrap();
+
+    assert_eq!(peaks.len(), 1);
+    assert_peak(&file, &peaks[0], [20.3, 30.6], [3.0, 4.0], 1000.0);
+    assert_eq!(peaks[0].eta, None);
+    assert!(peaks[0].residual < 0.1);
+    assert!(peaks[0].converged);
+    let volume = 1000.0 * 3.0 * 4.0 / 4.0 * PI / LN_2;
+    assert_float_eq!(peaks[0].volume, volume, rel <= 1e-3);
+}
+
+#[test]
+fn lorentzian_single() {
+    let file = spectrum(lorentzian, &[([20.3, 30.6], [2.5, 3.5], 500.0)]);
+    let fitter = PeakFitter::new(Lineshape::Lorentzian).radius(&[10, 12]);
+    let peaks = file
+        .fit_peaks(&[ppms(&file, [20.0, 31.0])], &fitter)
+        .unwrap();
+
+    assert_peak(&file, &peaks[0], [20.3, 30.6], [2.5, 3.5], 500.0);
+    let volume = 500.0 * PI * 2.5 / 2.0 * PI * 3.5 / 2.0;
+    assert_float_eq!(peaks[0].volume, volume, rel <= 1e-3);
+}
+
+#[test]
+fn pseudo_voigt() {
+    let eta = 0.3;
+    let shape =
+        |offset, width| eta * lorentzian(offset, width) + (1.0 - eta) * gaussian(offset, width);
+    let file = spectrum(shape, &[([20.0, 30.0], [3.0, 3.0], 800.0)]);
+    let fitter = PeakFitter::new(Lineshape::PseudoVoigt).radius(&[8, 8]);
+    let peaks = file
+        .fit_peaks(&[ppms(&file, [20.0, 30.0])], &fitter)
+        .unwrap();
+
+    assert_peak(&file, &peaks[0], [20.0, 30.0], [3.0, 3.0], 800.0);
+    assert_float_eq!(peaks[0].eta.unwrap(), eta, abs <= 1e-3);
+}
+
+#[test]
+fn overlapping_peaks_fitted_together() {
+    let file = spectrum(
+        gaussian,
+        &[
+            ([20.0, 28.0], [3.0, 4.0], 1000.0),
+            ([21.0, 33.0], [3.0, 4.0], 600.0),
+            ([40.0, 10.0], [3.0, 3.0], 300.0),
+        ],
+    );
+    let fitter = PeakFitter::new(Lineshape::Gaussian);
+    let positions = [
+        ppms(&file, [20.0, 28.0]),
+        ppms(&file, [21.0, 33.0]),
+        ppms(&file, [40.0, 10.0]),
+    ];
+    let peaks = file.fit_peaks(&positions, &fitter).unwrap();
+
+    assert_eq!(peaks[0].group, peaks[1].group);
+    assert_ne!(peaks[0].group, peaks[2].group);
+    assert_peak(&file, &peaks[0], [20.0, 28.0], [3.0, 4.0], 1000.0);
+    assert_peak(&file, &peaks[1], [21.0, 33.0], [3.0, 4.0], 600.0);
+    assert_peak(&file, &peaks[2], [40.0, 10.0], [3.0, 3.0], 300.0);
+    assert!(peaks.iter().all(|peak| peak.converged));
+
+    let peaks = file
+        .fit_peaks(&positions, &fitter.max_iterations(1))
+        .unwrap();
+    assert!(peaks.iter().all(|peak| !peak.converged));
+}
+
+#[test]
+fn invalid_positions() {
+    let file = spectrum(gaussian, &[([20.0, 30.0], [3.0, 3.0], 100.0)]);
+    let fitter = PeakFitter::new(Lineshape::Gaussian);
+    assert_eq!(
+        file.fit_peaks(&[vec![118.0]], &fitter).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        file.fit_peaks(&[vec![500.0, 8.0]], &fitter).unwrap_err(),
+        UcsfError::OutOfBounds
+    );
+}
+
+#[test]
+fn noise_stays_inside_spectrum() {
+    let file = hsqc();
+    let fitter = PeakFitter::new(Lineshape::PseudoVoigt);
+    let corner = vec![
+        file.axis_headers[0].index_to_ppm(0.0),
+        file.axis_headers[1].index_to_ppm(0.0),
+    ];
+    let peaks = file.fit_peaks(&[corner], &fitter).unwrap();
+
+    for (ppm, axis_header) in peaks[0].ppms.iter().zip(&file.axis_headers) {
+        let index = axis_header.ppm_to_index(*ppm);
+        assert!(index >= 0.0 && index <= (axis_header.data_points - 1) as f64);
+    }
+}
+
+#[test]
+fn fit_real_spectrum() {
+    let file = hsqc();
+    let picker = PeakPicker::new().positive(Threshold::NoiseMultiple(20.0));
+    let positions: Vec<_> = file
+        .pick_peaks(&picker)
+        .unwrap()
+        .into_iter()
+        .map(|peak| peak.ppms)
+        .collect();
+    assert!(positions.len() > 100);
+
+    let peaks = file
+        .fit_peaks(&positions, &PeakFitter::new(Lineshape::PseudoVoigt))
+        .unwrap();
+    assert_eq!(peaks.len(), positions.len());
+    assert!(peaks.iter().filter(|peak| peak.converged).count() > peaks.len() / 2);
+}
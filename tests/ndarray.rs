@@ -0,0 +1,85 @@
+#![cfg(feature = "ndarray")]
+
+mod common;
+
+use common::highres_hsqc;
+use ndarray::{ArrayD, IxDyn};
+use ucsf_nmr::{AxisHeader, UcsfError, UcsfFile};
+
+#[test]
+fn to_array() {
+    let file = highres_hsqc();
+    let array = file.to_array();
+
+    assert_eq!(array.shape(), &[512, 257]);
+    assert_eq!(array[[0, 0]], file.get(&[0, 0]).unwrap());
+    assert_eq!(array[[300, 256]], file.get(&[300, 256]).unwrap());
+    assert_eq!(array[[511, 100]], file.get(&[511, 100]).unwrap());
+    assert!(file.to_imaginary_array().is_none());
+}
+
+#[test]
+fn from_array_roundtrip() {
+    let file = highres_hsqc();
+    let array = file.to_array();
+
+    let built =
+        UcsfFile::from_array(array.clone(), file.axis_headers.clone()).expect("Failed building");
+    assert_eq!(built.to_array(), array);
+    assert_eq!(built.axis_sizes(), file.axis_sizes());
+}
+
+#[test]
+fn from_array_errors() {
+    let array = ArrayD::<f32>::zeros(IxDyn(&[4, 5]));
+    let axis = |data_points| AxisHeader::new("1H", data_points, 600.0, 6000.0, 4.7);
+
+    assert_eq!(
+        UcsfFile::from_array(array.clone(), vec![axis(4)]).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        UcsfFile::from_array(array, vec![axis(5), axis(4)]).unwrap_err(),
+        UcsfError::InvalidAxisSize(0)
+    );
+}
+
+#[test]
+fn tile_array_views() {
+    let file = highres_hsqc();
+    let array = file.to_array();
+
+    for tile in file.tiles() {
+        let view = tile.array_view();
+        assert_eq!(view.shape(), &tile.axis_lengths[..]);
+        // Views borrow the tile data
+        assert_eq!(view.as_ptr(), tile.data().as_ptr());
+        for (indices, value) in view.indexed_iter() {
+            let abs = [
+                indices[0] + tile.axis_starts[0],
+                indices[1] + tile.axis_starts[1],
+            ];
+            assert_eq!(*value, array[abs]);
+        }
+    }
+}
+
+#[test]
+fn tile_imaginary_array_views() {
+    let mut file = highres_hsqc();
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| -value).collect());
+
+    let tile = file.tiles().nth(4).unwrap();
+    let view = tile.array_view();
+    let imaginary_view = tile.imaginary_array_view().unwrap();
+    assert_eq!(imaginary_view.shape(), &[128, 1]);
+    assert_eq!(imaginary_view, view.mapv(|value| -value));
+    assert_eq!(
+        file.to_imaginary_array().unwrap(),
+        file.to_array().mapv(|value| -value)
+    );
+}
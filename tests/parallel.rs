@@ -0,0 +1,82 @@
+#![cfg(feature = "rayon")]
+
+mod common;
+
+use rayon::prelude::*;
+
+use common::synthetic_file;
+use ucsf_nmr::UcsfFile;
+
+#[test]
+fn par_parse() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let (rem, par_file) = UcsfFile::par_parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(par_file.header, file.header);
+    assert_eq!(par_file.axis_headers, file.axis_headers);
+    assert_eq!(par_file.data, file.data);
+    assert_eq!(par_file.imaginary_data, None);
+}
+
+#[test]
+fn par_parse_complex() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, mut file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| value * 3.0).collect());
+    let contents = file.to_bytes();
+
+    let (_, par_file) = UcsfFile::par_parse(&contents).expect("Failed parsing");
+    assert_eq!(par_file.data, file.data);
+    assert_eq!(par_file.imaginary_data, file.imaginary_data);
+    assert_eq!(
+        par_file.par_imaginary_data_continous(),
+        file.imaginary_data_continous()
+    );
+}
+
+#[test]
+fn par_tiles() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(file.par_tiles().count(), 20);
+
+    let sums: Vec<f32> = file
+        .par_tiles()
+        .map(|tile| tile.iter_with_abolute_pos().map(|(_, value)| value).sum())
+        .collect();
+    let expected: Vec<f32> = file
+        .tiles()
+        .map(|tile| tile.iter_with_abolute_pos().map(|(_, value)| value).sum())
+        .collect();
+    assert_eq!(sums, expected);
+}
+
+#[test]
+fn par_data_continous() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(file.par_data_continous(), file.data_continous());
+
+    for (sizes, tile_sizes) in [
+        (vec![30], vec![8]),
+        (vec![5, 6, 7], vec![2, 4, 3]),
+        (vec![3, 7, 4, 5], vec![2, 3, 2, 2]),
+    ]
+    .iter()
+    {
+        let file = synthetic_file(sizes, tile_sizes);
+        assert_eq!(file.par_data_continous(), file.data_continous());
+    }
+}
+
+#[test]
+fn par_data_continous_without_axes() {
+    let file = synthetic_file(&[], &[]);
+    assert_eq!(file.par_data_continous(), file.data_continous());
+}
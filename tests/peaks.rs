@@ -0,0 +1,143 @@
+mod common;
+
+use common::{gaussian_spectrum, hsqc, hsqc_axes};
+use ucsf_nmr::{AxisHeader, AxisRegion, PeakPicker, Separation, Threshold, UcsfError, UcsfFile};
+
+/// 2D spectrum with 8x8 tiles and gaussian peaks at the given indices.
+fn spectrum_with_peaks(peaks: &[([usize; 2], f64)]) -> UcsfFile {
+    // Full width at half maximum of 4 * sqrt(ln 2), i.e. exp(-distance^2 / 4)
+    let width = 4.0 * std::f64::consts::LN_2.sqrt();
+    let peaks: Vec<_> = peaks
+        .iter()
+        .map(|([x, y], height)| ([*x as f64, *y as f64], [width, width], *height))
+        .collect();
+    gaussian_spectrum(&hsqc_axes([32, 40], [1200.0, 2400.0]), [8, 8], &peaks)
+}
+
+#[test]
+fn positive_peaks_across_tiles() {
+    // (8, 8) and (15, 24) lie directly at tile boundaries
+    let file = spectrum_with_peaks(&[([8, 8], 100.0), ([15, 24], 50.0), ([25, 5], 5.0)]);
+    let picker = PeakPicker::new().positive(Threshold::Absolute(10.0));
+    let peaks = file.pick_peaks(&picker).unwrap();
+
+    assert_eq!(peaks.len(), 2);
+    assert_eq!(peaks[0].indices, vec![8, 8]);
+    assert_eq!(peaks[0].height, 100.0);
+    assert_eq!(peaks[1].indices, vec![15, 24]);
+    assert_eq!(
+        peaks[1].ppms,
+        vec![
+            file.axis_headers[0].index_to_ppm(15.0),
+            file.axis_headers[1].index_to_ppm(24.0)
+        ]
+    );
+}
+
+#[test]
+fn negative_peaks() {
+    let file = spectrum_with_peaks(&[([8, 8], 100.0), ([20, 30], -80.0)]);
+
+    let picker = PeakPicker::new().negative(Threshold::Absolute(10.0));
+    let peaks = file.pick_peaks(&picker).unwrap();
+    assert_eq!(peaks.len(), 1);
+    assert_eq!(peaks[0].indices, vec![20, 30]);
+    assert_eq!(peaks[0].height, -80.0);
+
+    let picker = picker.positive(Threshold::Absolute(10.0));
+    let peaks = file.pick_peaks(&picker).unwrap();
+    assert_eq!(peaks.len(), 2);
+    assert_eq!(peaks[0].height, 100.0);
+    assert_eq!(peaks[1].height, -80.0);
+}
+
+#[test]
+fn no_threshold_no_peaks() {
+    let file = spectrum_with_peaks(&[([8, 8], 100.0)]);
+    assert_eq!(file.pick_peaks(&PeakPicker::new()).unwrap(), vec![]);
+}
+
+#[test]
+fn separation() {
+    let file = spectrum_with_peaks(&[([10, 10], 100.0), ([10, 16], 60.0)]);
+    let picker = PeakPicker::new().positive(Threshold::Absolute(10.0));
+    assert_eq!(file.pick_peaks(&picker).unwrap().len(), 2);
+
+    let separated = picker
+        .clone()
+        .separation(&[Separation::Points(3), Separation::Points(10)]);
+    let peaks = file.pick_peaks(&separated).unwrap();
+    assert_eq!(peaks.len(), 1);
+    assert_eq!(peaks[0].indices, vec![10, 10]);
+
+    // 6 points along the second axis are 0.025 ppm
+    let separated = picker
+        .clone()
+        .separation(&[Separation::Ppm(0.1), Separation::Ppm(0.02)]);
+    assert_eq!(file.pick_peaks(&separated).unwrap().len(), 2);
+
+    let wrong_dimensions = picker.separation(&[Separation::Points(3)]);
+    assert_eq!(
+        file.pick_peaks(&wrong_dimensions).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+}
+
+#[test]
+fn region() {
+    let file = spectrum_with_peaks(&[([8, 8], 100.0), ([15, 24], 50.0)]);
+    let picker = PeakPicker::new()
+        .positive(Threshold::Absolute(10.0))
+        .region(&[AxisRegion::Full, AxisRegion::Index(20..40)]);
+    let peaks = file.pick_peaks(&picker).unwrap();
+    assert_eq!(peaks.len(), 1);
+    assert_eq!(peaks[0].indices, vec![15, 24]);
+
+    // The neighbours outside of the region are still considered
+    let picker = PeakPicker::new()
+        .positive(Threshold::Absolute(10.0))
+        .region(&[AxisRegion::Full, AxisRegion::Index(9..40)]);
+    let peaks = file.pick_peaks(&picker).unwrap();
+    assert_eq!(peaks.len(), 1);
+}
+
+#[test]
+fn noise_threshold() {
+    let file = hsqc();
+    let noise = file.noise_level().unwrap();
+    let picker = PeakPicker::new().positive(Threshold::NoiseMultiple(50.0));
+    let peaks = file.pick_peaks(&picker).unwrap();
+    assert!(!peaks.is_empty());
+    for peak in &peaks {
+        assert!(peak.height >= 50.0 * noise);
+        assert_eq!(file.get(&peak.indices), Some(peak.height));
+    }
+    assert!(peaks
+        .windows(2)
+        .all(|peaks| peaks[0].height >= peaks[1].height));
+}
+
+#[test]
+fn peaks_3d() {
+    let sizes = [6, 7, 8];
+    let mut data = vec![0f32; 6 * 7 * 8];
+    // Single maximum at (3, 3, 4), which lies at a tile corner
+    data[(3 * 7 + 3) * 8 + 4] = 10.0;
+    data[(3 * 7 + 3) * 8 + 3] = 5.0;
+    let file = UcsfFile::builder(data)
+        .axis(AxisHeader::new("13C", sizes[0] as u32, 150.0, 3000.0, 40.0))
+        .axis(AxisHeader::new("15N", sizes[1] as u32, 60.0, 1200.0, 118.0))
+        .axis(AxisHeader::new("1H", sizes[2] as u32, 600.0, 2400.0, 8.0))
+        .tile_sizes(&[3, 3, 4])
+        .build()
+        .unwrap();
+
+    let picker = PeakPicker::new().positive(Threshold::Absolute(1.0));
+    let peaks = file.pick_peaks(&picker).unwrap();
+    assert_eq!(peaks.len(), 1);
+    assert_eq!(peaks[0].indices, vec![3, 3, 4]);
+    assert_eq!(peaks[0].ppms.len(), 3);
+}
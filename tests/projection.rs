@@ -0,0 +1,128 @@
+mod common;
+
+use float_eq::assert_float_eq;
+
+use common::{continous_position, synthetic_file};
+use ucsf_nmr::{AxisProjection, AxisRegion, ProjectionMode, UcsfError, UcsfFile};
+
+#[test]
+fn project_3d_along_first_axis() {
+    let file = synthetic_file(&[4, 5, 6], &[3, 2, 4]);
+    let sizes = [4, 5, 6];
+    let axes = [
+        AxisProjection::Collapse(AxisRegion::Full),
+        AxisProjection::Keep,
+        AxisProjection::Keep,
+    ];
+
+    let max = file.project(&axes, ProjectionMode::Max).unwrap();
+    let min = file.project(&axes, ProjectionMode::Min).unwrap();
+    let sum = file.project(&axes, ProjectionMode::Sum).unwrap();
+    let mean = file.project(&axes, ProjectionMode::Mean).unwrap();
+    assert_eq!(max.header.dimensions, 2);
+    assert_eq!(max.axis_headers, file.axis_headers[1..].to_vec());
+
+    for j in 0..5 {
+        for k in 0..6 {
+            let values: Vec<_> = (0..4)
+                .map(|i| continous_position(&sizes, &[i, j, k]) as f32)
+                .collect();
+            let expected_sum: f32 = values.iter().sum();
+            assert_eq!(max.get(&[j, k]), Some(values[3]));
+            assert_eq!(min.get(&[j, k]), Some(values[0]));
+            assert_eq!(sum.get(&[j, k]), Some(expected_sum));
+            assert_eq!(mean.get(&[j, k]), Some(expected_sum / 4.0));
+        }
+    }
+}
+
+#[test]
+fn project_3d_to_1d() {
+    let file = synthetic_file(&[4, 5, 6], &[3, 2, 4]);
+    let sizes = [4, 5, 6];
+
+    let projection = file
+        .project(
+            &[
+                AxisProjection::Collapse(AxisRegion::Index(1..3)),
+                AxisProjection::Keep,
+                AxisProjection::Collapse(AxisRegion::Full),
+            ],
+            ProjectionMode::Sum,
+        )
+        .unwrap();
+    assert_eq!(projection.axis_sizes(), vec![5]);
+    for j in 0..5 {
+        let expected: usize = (1..3)
+            .flat_map(|i| (0..6).map(move |k| continous_position(&sizes, &[i, j, k])))
+            .sum();
+        assert_eq!(projection.get(&[j]), Some(expected as f32));
+    }
+}
+
+#[test]
+fn project_hsqc_abs_max_with_ppm_range() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let data = file.data_continous();
+
+    let projection = file
+        .project(
+            &[
+                AxisProjection::Keep,
+                AxisProjection::Collapse(AxisRegion::Ppm(7.0, 9.0)),
+            ],
+            ProjectionMode::AbsMax,
+        )
+        .unwrap();
+    let range = file.axis_headers[1].ppm_to_index_range(7.0, 9.0);
+    assert_eq!(projection.axis_headers, vec![file.axis_headers[0].clone()]);
+
+    for i in 0..512 {
+        let expected =
+            range
+                .clone()
+                .map(|j| data[i * 257 + j])
+                .fold(0f32, |acc, value| match value.abs() > acc.abs() {
+                    true => value,
+                    false => acc,
+                });
+        assert_float_eq!(projection.get(&[i]).unwrap(), expected, ulps <= 0);
+    }
+}
+
+#[test]
+fn project_errors() {
+    let file = synthetic_file(&[4, 5], &[3, 2]);
+
+    assert_eq!(
+        file.project(
+            &[
+                AxisProjection::Collapse(AxisRegion::Full),
+                AxisProjection::Collapse(AxisRegion::Full)
+            ],
+            ProjectionMode::Max
+        )
+        .unwrap_err(),
+        UcsfError::NoAxesKept
+    );
+    assert_eq!(
+        file.project(&[AxisProjection::Keep], ProjectionMode::Max)
+            .unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        file.project(
+            &[
+                AxisProjection::Keep,
+                AxisProjection::Collapse(AxisRegion::Index(7..9))
+            ],
+            ProjectionMode::Max
+        )
+        .unwrap_err(),
+        UcsfError::EmptyRegion(1)
+    );
+}
@@ -0,0 +1,94 @@
+use std::fs::File;
+use std::io::Cursor;
+
+use ucsf_nmr::{UcsfError, UcsfFile, UcsfReader};
+
+fn assert_same_tiles(path: &str) {
+    let contents = std::fs::read(path).unwrap();
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    let mut reader = UcsfReader::new(File::open(path).unwrap()).expect("Failed reading");
+    assert_eq!(reader.header(), &file.header);
+    assert_eq!(reader.axis_headers(), &file.axis_headers[..]);
+    assert_eq!(reader.axis_tiles(), file.axis_tiles());
+    assert_eq!(reader.axis_tile_sizes(), file.axis_tile_sizes());
+    assert_eq!(reader.axis_sizes(), file.axis_sizes());
+    assert_eq!(reader.num_tiles(), file.tiles().count());
+
+    for (tile, read_tile) in file.tiles().zip(reader.tiles()) {
+        let read_tile = read_tile.expect("Failed reading tile");
+        let read_tile = read_tile.tile();
+        assert_eq!(tile.axis_lengths, read_tile.axis_lengths);
+        assert_eq!(tile.axis_starts, read_tile.axis_starts);
+        assert_eq!(tile.data(), read_tile.data());
+        assert!(tile
+            .iter_with_abolute_pos()
+            .eq(read_tile.iter_with_abolute_pos()));
+    }
+}
+
+#[test]
+fn read_tiles() {
+    assert_same_tiles("./tests/data/15n_hsqc.ucsf");
+}
+
+#[test]
+fn read_tiles_padded() {
+    assert_same_tiles("./tests/data/Nhsqc_highres_600MHz.ucsf");
+}
+
+#[test]
+fn read_single_tile() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    let mut reader = UcsfReader::new(Cursor::new(&contents[..])).expect("Failed reading");
+    let tile = reader.read_tile(14).expect("Failed reading tile");
+    assert_eq!(tile.axis_starts, vec![256, 256]);
+    assert_eq!(tile.axis_lengths, vec![128, 1]);
+    assert_eq!(tile.data, file.tiles().nth(14).unwrap().data());
+
+    // Reading tiles out of order
+    let tile = reader.read_tile(2).expect("Failed reading tile");
+    assert_eq!(tile.data, file.tiles().nth(2).unwrap().data());
+}
+
+#[test]
+fn read_complex_tile() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+    let (_, mut file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| value + 1.0).collect());
+
+    let mut reader = UcsfReader::new(Cursor::new(file.to_bytes())).expect("Failed reading");
+    let tile = reader.read_tile(3).expect("Failed reading tile");
+    let file_tile = file.tiles().nth(3).unwrap();
+    assert_eq!(tile.data, file_tile.data());
+    assert_eq!(tile.imaginary_data.as_deref(), file_tile.imaginary_data());
+}
+
+#[test]
+fn read_tile_out_of_range() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let mut reader = UcsfReader::new(Cursor::new(&contents[..])).expect("Failed reading");
+    assert_eq!(reader.read_tile(4).unwrap_err(), UcsfError::TileOutOfRange);
+}
+
+#[test]
+fn read_truncated_file() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let mut reader =
+        UcsfReader::new(Cursor::new(&contents[..contents.len() - 1])).expect("Failed reading");
+    assert!(reader.read_tile(0).is_ok());
+    assert_eq!(
+        reader.read_tile(3).unwrap_err(),
+        UcsfError::Io(std::io::ErrorKind::UnexpectedEof)
+    );
+
+    assert_eq!(
+        UcsfReader::new(Cursor::new(&contents[..300])).unwrap_err(),
+        UcsfError::Io(std::io::ErrorKind::UnexpectedEof)
+    );
+}
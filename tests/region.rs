@@ -0,0 +1,136 @@
+mod common;
+
+use float_eq::assert_float_eq;
+
+use common::highres_hsqc;
+use ucsf_nmr::{AxisRegion, UcsfError, UcsfFile};
+
+#[test]
+fn region_by_index() {
+    let file = highres_hsqc();
+    let region = file
+        .region(&[AxisRegion::Index(100..300), AxisRegion::Index(50..257)])
+        .expect("Failed extracting region");
+
+    assert_eq!(region.axis_sizes(), vec![200, 207]);
+    assert_eq!(region.axis_tile_sizes(), file.axis_tile_sizes());
+    assert_eq!(region.data.len(), 256 * 256);
+
+    let data = file.data_continous();
+    let region_data = region.data_continous();
+    for i_axis_1 in 0..200 {
+        for i_axis_2 in 0..207 {
+            assert_eq!(
+                region_data[i_axis_1 * 207 + i_axis_2],
+                data[(i_axis_1 + 100) * 257 + i_axis_2 + 50]
+            );
+        }
+    }
+
+    // Padding of the re-tiled data is zero
+    let last_tile = region.tiles().last().unwrap();
+    assert_eq!(last_tile.axis_lengths, vec![72, 15]);
+    for (i, value) in last_tile.data().iter().enumerate() {
+        if i / 64 >= 72 || i % 64 >= 15 {
+            assert_eq!(*value, 0.0);
+        }
+    }
+}
+
+#[test]
+fn region_axis_headers() {
+    let file = highres_hsqc();
+    let region = file
+        .region(&[AxisRegion::Index(100..300), AxisRegion::Full])
+        .expect("Failed extracting region");
+
+    let axis = &file.axis_headers[0];
+    let region_axis = &region.axis_headers[0];
+    assert_eq!(region_axis.nucleus_name, axis.nucleus_name);
+    assert_eq!(region_axis.data_points, 200);
+    assert_float_eq!(region_axis.frequency, axis.frequency, ulps <= 0);
+    assert_float_eq!(
+        region_axis.spectral_width as f64,
+        axis.spectral_width as f64 * 200.0 / 512.0,
+        rel <= 1e-6
+    );
+    for index in [0.0, 50.0, 199.0].iter() {
+        assert_float_eq!(
+            region_axis.index_to_ppm(*index),
+            axis.index_to_ppm(index + 100.0),
+            abs <= 1e-4
+        );
+    }
+
+    assert_eq!(region.axis_headers[1], file.axis_headers[1]);
+}
+
+#[test]
+fn region_by_ppm() {
+    let file = highres_hsqc();
+    let region = file
+        .region(&[AxisRegion::Ppm(130.0, 105.0), AxisRegion::Ppm(6.0, 10.0)])
+        .expect("Failed extracting region");
+
+    let n_range = file.axis_headers[0].ppm_to_index_range(105.0, 130.0);
+    let h_range = file.axis_headers[1].ppm_to_index_range(6.0, 10.0);
+    assert_eq!(region.axis_sizes(), vec![n_range.len(), h_range.len()]);
+
+    let (min, max) = region.axis_headers[1].ppm_range();
+    assert!(min >= 6.0 - 1e-4);
+    assert!(max <= 10.0 + 1e-4);
+}
+
+#[test]
+fn region_roundtrip() {
+    let file = highres_hsqc();
+    let region = file
+        .region(&[AxisRegion::Index(0..70), AxisRegion::Index(3..9)])
+        .expect("Failed extracting region");
+    assert_eq!(region.axis_tile_sizes(), vec![70, 6]);
+
+    let bytes = region.to_bytes();
+    let (rem, parsed) = UcsfFile::parse(&bytes).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.axis_headers, region.axis_headers);
+    assert_eq!(parsed.data, region.data);
+}
+
+#[test]
+fn region_complex() {
+    let mut file = highres_hsqc();
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| -value).collect());
+
+    let region = file
+        .region(&[AxisRegion::Index(10..20), AxisRegion::Index(60..70)])
+        .expect("Failed extracting region");
+    let real = region.data_continous();
+    let imaginary = region.imaginary_data_continous().unwrap();
+    for (real, imaginary) in real.iter().zip(&imaginary) {
+        assert_eq!(*imaginary, -real);
+    }
+}
+
+#[test]
+fn region_errors() {
+    let file = highres_hsqc();
+
+    assert_eq!(
+        file.region(&[AxisRegion::Full]).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        file.region(&[AxisRegion::Full, AxisRegion::Index(300..400)])
+            .unwrap_err(),
+        UcsfError::EmptyRegion(1)
+    );
+    assert_eq!(
+        file.region(&[AxisRegion::Ppm(300.0, 400.0), AxisRegion::Full])
+            .unwrap_err(),
+        UcsfError::EmptyRegion(0)
+    );
+}
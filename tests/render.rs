@@ -0,0 +1,68 @@
+#![cfg(feature = "render")]
+
+mod common;
+
+use common::{hsqc, synthetic_file};
+use std::fs;
+use std::path::PathBuf;
+use ucsf_nmr::{Colormap, RenderOptions, RenderStyle, UcsfError};
+
+fn output_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ucsf_nmr_render_{}", name))
+}
+
+#[test]
+fn contour_svg() {
+    let file = hsqc();
+    let path = output_path("contour.svg");
+    let options = RenderOptions::new(RenderStyle::AutoContour).title("15N HSQC");
+    file.render_svg(&path, &options).expect("Failed rendering");
+
+    let svg = fs::read_to_string(&path).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("15N HSQC"));
+    assert!(svg.contains(&format!("{} (ppm)", file.axis_headers[0].nucleus_name)));
+    assert!(svg.contains(&format!("{} (ppm)", file.axis_headers[1].nucleus_name)));
+    assert!(svg.contains("<polyline"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn image_png() {
+    let file = hsqc();
+    let path = output_path("image.png");
+    let options = RenderOptions::new(RenderStyle::Image)
+        .colormap(Colormap::Viridis)
+        .size(400, 300);
+    file.render_png(&path, &options).expect("Failed rendering");
+
+    let png = fs::read(&path).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn explicit_levels_and_range() {
+    let file = hsqc();
+    let (_, max) = file.bounds();
+    let path = output_path("levels.png");
+    let options = RenderOptions::new(RenderStyle::Contour(vec![max / 4.0, max / 2.0]))
+        .colormap(Colormap::Greys)
+        .value_range(0.0, max / 10.0);
+    file.render_png(&path, &options).expect("Failed rendering");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn only_2d() {
+    let file = synthetic_file(&[4, 6, 8], &[2, 3, 4]);
+    let options = RenderOptions::new(RenderStyle::Image);
+    assert_eq!(
+        file.render_png(output_path("3d.png"), &options)
+            .unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 3
+        }
+    );
+}
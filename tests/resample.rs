@@ -0,0 +1,200 @@
+mod common;
+
+use common::{continous_indices, gaussian, hsqc, hsqc_axes, spectrum_2d, synthetic_file};
+use float_eq::assert_float_eq;
+use std::f64::consts::LN_2;
+use ucsf_nmr::{AxisHeader, Interpolation, OutsideValue, UcsfError, UcsfFile};
+
+/// 2D spectrum with 6x10 tiles, whose values are given by `value` at the ppm positions.
+fn spectrum_from_ppm(axes: &[AxisHeader; 2], value: impl Fn(f64, f64) -> f64) -> UcsfFile {
+    spectrum_2d(axes, [6, 10], |x, y| {
+        value(axes[0].index_to_ppm(x), axes[1].index_to_ppm(y))
+    })
+}
+
+fn source_axes() -> [AxisHeader; 2] {
+    hsqc_axes([32, 48], [1800.0, 3000.0])
+}
+
+/// Axes with more data points, a smaller spectral width and a shifted center, which lie within
+/// the source axes.
+fn finer_axes() -> [AxisHeader; 2] {
+    [
+        AxisHeader::new("15N", 50, 60.0, 1200.0, 117.3),
+        AxisHeader::new("1H", 70, 600.0, 2000.0, 8.4),
+    ]
+}
+
+/// Gaussian peak at 118 / 8.2 ppm, with a full width at half maximum of about 7.4 / 1.2 ppm.
+fn gaussian_peak(ppm_1: f64, ppm_2: f64) -> f64 {
+    let (width_1, width_2) = ((80.0 * LN_2).sqrt(), (2.0 * LN_2).sqrt());
+    100.0 * gaussian(ppm_1 - 118.0, width_1) * gaussian(ppm_2 - 8.2, width_2)
+}
+
+#[test]
+fn identity() {
+    let file = hsqc();
+    for interpolation in [Interpolation::Linear, Interpolation::Cubic] {
+        let resampled = file
+            .resample_like(&file, interpolation, OutsideValue::Nan)
+            .unwrap();
+        assert_eq!(resampled.axis_headers, file.axis_headers);
+        assert_eq!(resampled.axis_tile_sizes(), file.axis_tile_sizes());
+        for (a, b) in resampled.data_continous().iter().zip(file.data_continous()) {
+            assert_float_eq!(*a, b, abs <= 1e-4 * b.abs().max(1.0));
+        }
+    }
+}
+
+#[test]
+fn linear_function() {
+    // Linear interpolation reproduces linear functions exactly
+    let plane = |ppm_1: f64, ppm_2: f64| 3.0 * ppm_1 - 20.0 * ppm_2;
+    let source = spectrum_from_ppm(&source_axes(), plane);
+    let expected = spectrum_from_ppm(&finer_axes(), plane);
+
+    let resampled = source
+        .resample_like(&expected, Interpolation::Linear, OutsideValue::Nan)
+        .unwrap();
+    for (a, b) in resampled
+        .data_continous()
+        .iter()
+        .zip(expected.data_continous())
+    {
+        assert_float_eq!(*a, b, abs <= 1e-3);
+    }
+}
+
+#[test]
+fn cubic_is_more_accurate() {
+    let source = spectrum_from_ppm(&source_axes(), gaussian_peak);
+    let expected = spectrum_from_ppm(&finer_axes(), gaussian_peak);
+
+    let max_error = |interpolation| {
+        let resampled = source
+            .resample_like(&expected, interpolation, OutsideValue::Nan)
+            .unwrap();
+        resampled
+            .data_continous()
+            .iter()
+            .zip(expected.data_continous())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0f32, f32::max)
+    };
+    let linear = max_error(Interpolation::Linear);
+    let cubic = max_error(Interpolation::Cubic);
+    assert!(linear < 10.0);
+    assert!(cubic < linear / 2.0);
+}
+
+#[test]
+fn outside_values() {
+    let source = spectrum_from_ppm(&finer_axes(), gaussian_peak);
+    // The source window lies within the target window
+    let target = source_axes();
+    let inside = |indices: &[usize]| {
+        target
+            .iter()
+            .zip(indices)
+            .zip(&source.axis_headers)
+            .all(|((target, index), source)| {
+                let source_index = source.ppm_to_index(target.index_to_ppm(*index as f64));
+                source_index >= 0.0 && source_index <= (source.data_points - 1) as f64
+            })
+    };
+    let sizes = [
+        target[0].data_points as usize,
+        target[1].data_points as usize,
+    ];
+
+    let nan = source
+        .resample(&target, Interpolation::Cubic, OutsideValue::Nan)
+        .unwrap();
+    let zero = source
+        .resample(&target, Interpolation::Cubic, OutsideValue::Zero)
+        .unwrap();
+    let (nan, zero) = (nan.data_continous(), zero.data_continous());
+    let mut inside_count = 0;
+    for pos in 0..sizes[0] * sizes[1] {
+        if inside(&continous_indices(&sizes, pos)) {
+            inside_count += 1;
+            assert!(nan[pos].is_finite());
+            assert_eq!(nan[pos], zero[pos]);
+        } else {
+            assert!(nan[pos].is_nan());
+            assert_eq!(zero[pos], 0.0);
+        }
+    }
+    assert!(inside_count > 0 && inside_count < sizes[0] * sizes[1]);
+}
+
+#[test]
+fn arithmetic_after_resampling() {
+    let source = spectrum_from_ppm(&source_axes(), gaussian_peak);
+    let target = spectrum_from_ppm(&finer_axes(), gaussian_peak);
+    assert!(source.subtract(&target).is_err());
+
+    let resampled = source
+        .resample_like(&target, Interpolation::Cubic, OutsideValue::Zero)
+        .unwrap();
+    let difference = resampled.subtract(&target).unwrap();
+    let (min, max) = difference.bounds();
+    assert!(min > -5.0 && max < 5.0);
+}
+
+#[test]
+fn imaginary_data() {
+    let mut file = synthetic_file(&[8, 12], &[4, 6]);
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| -value).collect());
+
+    let mut axis_headers = file.axis_headers.clone();
+    axis_headers[1].center -= 0.25 * axis_headers[1].ppm_per_point() as f32;
+    let resampled = file
+        .resample(&axis_headers, Interpolation::Linear, OutsideValue::Zero)
+        .unwrap();
+    assert_eq!(resampled.header.components, 2);
+    let real = resampled.data_continous();
+    let imaginary = resampled.imaginary_data_continous().unwrap();
+    for (real, imaginary) in real.iter().zip(&imaginary) {
+        assert_float_eq!(*imaginary, -real, abs <= 1e-4);
+    }
+    // Shifted by a quarter data point along the second axis
+    assert_float_eq!(real[1], 1.25, abs <= 1e-4);
+}
+
+#[test]
+fn invalid_target_axes() {
+    let file = hsqc();
+    assert_eq!(
+        file.resample(
+            &file.axis_headers[..1],
+            Interpolation::Linear,
+            OutsideValue::Zero
+        )
+        .unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+
+    let mut axis_headers = file.axis_headers.clone();
+    axis_headers[1].nucleus_name = "13C".to_owned();
+    assert_eq!(
+        file.resample(&axis_headers, Interpolation::Linear, OutsideValue::Zero)
+            .unwrap_err(),
+        UcsfError::IncompatibleAxis {
+            axis: 1,
+            property: "nucleus"
+        }
+    );
+
+    let mut axis_headers = file.axis_headers.clone();
+    axis_headers[0].data_points = 0;
+    assert_eq!(
+        file.resample(&axis_headers, Interpolation::Linear, OutsideValue::Zero)
+            .unwrap_err(),
+        UcsfError::InvalidAxisSize(0)
+    );
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use float_eq::assert_float_eq;
+
+use common::{continous_position, highres_hsqc, synthetic_file};
+use ucsf_nmr::{AxisPosition, UcsfError};
+
+#[test]
+fn trace_along_axes() {
+    let file = highres_hsqc();
+    let data = file.data_continous();
+
+    let trace = file.trace(0, &[0, 256]).expect("Failed extracting trace");
+    assert_eq!(trace.values.len(), 512);
+    assert_eq!(trace.axis_header, file.axis_headers[0]);
+    for (i_axis_1, value) in trace.values.iter().enumerate() {
+        assert_eq!(*value, data[i_axis_1 * 257 + 256]);
+    }
+
+    let trace = file.trace(1, &[300, 0]).expect("Failed extracting trace");
+    assert_eq!(trace.values.len(), 257);
+    for (i_axis_2, value) in trace.values.iter().enumerate() {
+        assert_eq!(*value, data[300 * 257 + i_axis_2]);
+    }
+
+    // Index along the trace axis is ignored
+    let other = file.trace(1, &[300, 100]).expect("Failed extracting trace");
+    assert_eq!(trace.values, other.values);
+}
+
+#[test]
+fn trace_ppm_scale() {
+    let file = highres_hsqc();
+
+    let trace = file.trace(1, &[10, 0]).expect("Failed extracting trace");
+    assert_eq!(trace.ppm_scale.len(), trace.values.len());
+    assert_float_eq!(
+        trace.ppm_scale[42],
+        file.axis_headers[1].index_to_ppm(42.0),
+        abs <= 1e-12
+    );
+    assert!(trace.ppm_scale[0] > trace.ppm_scale[256]);
+}
+
+#[test]
+fn trace_by_ppm() {
+    let file = highres_hsqc();
+
+    let ppm = file.axis_headers[1].index_to_ppm(123.0);
+    let trace = file
+        .trace_ppm(0, &[0.0, ppm])
+        .expect("Failed extracting trace");
+    let expected = file.trace(0, &[0, 123]).expect("Failed extracting trace");
+    assert_eq!(trace.values, expected.values);
+}
+
+#[test]
+fn trace_errors() {
+    let file = highres_hsqc();
+
+    assert_eq!(
+        file.trace(2, &[0, 0]).unwrap_err(),
+        UcsfError::InvalidAxis(2)
+    );
+    assert_eq!(
+        file.trace(0, &[0, 257]).unwrap_err(),
+        UcsfError::OutOfBounds
+    );
+    assert_eq!(
+        file.trace(0, &[0]).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        file.trace_ppm(0, &[0.0, 100.0]).unwrap_err(),
+        UcsfError::OutOfBounds
+    );
+}
+
+#[test]
+fn plane_of_3d() {
+    let file = synthetic_file(&[5, 6, 7], &[2, 4, 3]);
+    let sizes = [5, 6, 7];
+
+    let plane = file
+        .plane(&[
+            AxisPosition::Free,
+            AxisPosition::Index(4),
+            AxisPosition::Free,
+        ])
+        .expect("Failed extracting plane");
+    assert_eq!(plane.header.dimensions, 2);
+    assert_eq!(plane.axis_sizes(), vec![5, 7]);
+    assert_eq!(plane.axis_headers[0], file.axis_headers[0]);
+    assert_eq!(plane.axis_headers[1], file.axis_headers[2]);
+    for i_axis_1 in 0..5 {
+        for i_axis_3 in 0..7 {
+            assert_eq!(
+                plane.get(&[i_axis_1, i_axis_3]),
+                Some(continous_position(&sizes, &[i_axis_1, 4, i_axis_3]) as f32)
+            );
+        }
+    }
+
+    // Padding of the plane is zero
+    let last_tile = plane.tiles().last().unwrap();
+    assert_eq!(last_tile.axis_lengths, vec![1, 1]);
+    assert_eq!(&last_tile.data()[1..], &[0.0; 5][..]);
+}
+
+#[test]
+fn plane_of_4d_by_ppm() {
+    let file = synthetic_file(&[3, 4, 5, 6], &[2, 2, 2, 2]);
+    let sizes = [3, 4, 5, 6];
+
+    let ppm_1 = file.axis_headers[0].index_to_ppm(2.0);
+    let ppm_3 = file.axis_headers[2].index_to_ppm(1.0);
+    let plane = file
+        .plane(&[
+            AxisPosition::Ppm(ppm_1),
+            AxisPosition::Free,
+            AxisPosition::Ppm(ppm_3),
+            AxisPosition::Free,
+        ])
+        .expect("Failed extracting plane");
+    assert_eq!(plane.axis_sizes(), vec![4, 6]);
+    let data = plane.data_continous();
+    for i_axis_2 in 0..4 {
+        for i_axis_4 in 0..6 {
+            assert_eq!(
+                data[i_axis_2 * 6 + i_axis_4],
+                continous_position(&sizes, &[2, i_axis_2, 1, i_axis_4]) as f32
+            );
+        }
+    }
+}
+
+#[test]
+fn plane_errors() {
+    let file = synthetic_file(&[5, 6, 7], &[2, 4, 3]);
+
+    assert_eq!(
+        file.plane(&[AxisPosition::Free, AxisPosition::Free, AxisPosition::Free])
+            .unwrap_err(),
+        UcsfError::FreeAxesMismatch {
+            expected: 2,
+            actual: 3
+        }
+    );
+    assert_eq!(
+        file.plane(&[AxisPosition::Free, AxisPosition::Free])
+            .unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 3,
+            actual: 2
+        }
+    );
+    assert_eq!(
+        file.plane(&[
+            AxisPosition::Free,
+            AxisPosition::Index(6),
+            AxisPosition::Free
+        ])
+        .unwrap_err(),
+        UcsfError::OutOfBounds
+    );
+    assert_eq!(
+        file.plane(&[
+            AxisPosition::Free,
+            AxisPosition::Ppm(100.0),
+            AxisPosition::Free
+        ])
+        .unwrap_err(),
+        UcsfError::OutOfBounds
+    );
+}
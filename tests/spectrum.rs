@@ -0,0 +1,69 @@
+mod common;
+
+use common::{continous_position, synthetic_file};
+use std::convert::TryFrom;
+use ucsf_nmr::{Spectrum, UcsfError, UcsfFile};
+
+#[test]
+fn try_from_checks_dimensions() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(
+        Spectrum::<3>::try_from(file.clone()).unwrap_err(),
+        UcsfError::DimensionMismatch {
+            expected: 3,
+            actual: 2
+        }
+    );
+    assert!(file.into_spectrum::<2>().is_ok());
+}
+
+#[test]
+fn iter_matches_untyped() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let spectrum = Spectrum::<2>::try_from(file.clone()).unwrap();
+    for (tile, typed_tile) in file.tiles().zip(spectrum.tiles()) {
+        assert_eq!(typed_tile.axis_starts().to_vec(), tile.axis_starts);
+        let typed: Vec<_> = typed_tile
+            .iter_with_abolute_pos()
+            .map(|(pos, value)| (pos.to_vec(), value))
+            .collect();
+        let untyped: Vec<_> = tile
+            .iter_with_abolute_pos()
+            .map(|(pos, value)| (pos.to_vec(), value))
+            .collect();
+        assert_eq!(typed, untyped);
+    }
+}
+
+#[test]
+fn iter_3d() {
+    let file = synthetic_file(&[5, 6, 7], &[2, 3, 4]);
+    let spectrum = file.into_spectrum::<3>().unwrap();
+    assert_eq!(spectrum.axis_sizes(), [5, 6, 7]);
+
+    let mut count = 0;
+    for tile in spectrum.tiles() {
+        for (pos, value) in tile.iter_with_abolute_pos() {
+            assert_eq!(value, continous_position(&[5, 6, 7], &pos) as f32);
+            assert_eq!(spectrum.get(pos), Some(value));
+            count += 1;
+        }
+    }
+    assert_eq!(count, 5 * 6 * 7);
+}
+
+#[test]
+fn ppm_round_trip() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let spectrum = file.into_spectrum::<2>().unwrap();
+    let ppms = spectrum.indices_to_ppm([100, 200]);
+    assert_eq!(spectrum.ppm_to_indices(ppms), Some([100, 200]));
+    assert_eq!(spectrum.get_ppm(ppms), spectrum.get([100, 200]));
+    assert_eq!(spectrum.get([usize::MAX, 0]), None);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::synthetic_file;
+use float_eq::assert_float_eq;
+use ucsf_nmr::UcsfFile;
+
+#[test]
+fn statistics_exclude_padding() {
+    // Values are 0..130, padding is -1.0
+    let file = synthetic_file(&[10, 13], &[4, 6]);
+    let statistics = file.statistics().unwrap();
+    assert_eq!(statistics.count, 130);
+    assert_eq!(statistics.min, 0.0);
+    assert_eq!(statistics.max, 129.0);
+    assert_float_eq!(statistics.mean, 64.5, abs <= 1e-9);
+    let rms = ((0..130).map(|v| (v * v) as f64).sum::<f64>() / 130.0).sqrt();
+    assert_float_eq!(statistics.rms, rms, rel <= 1e-9);
+    assert_eq!(file.bounds(), (0.0, 129.0));
+}
+
+#[test]
+fn statistics_ignore_nan() {
+    let mut file = synthetic_file(&[8, 12], &[4, 6]);
+    file.data[5] = f32::NAN;
+    file.data[7] = f32::INFINITY;
+    let statistics = file.statistics().unwrap();
+    assert_eq!(statistics.count, 94);
+    assert!(file.bounds().0.is_finite());
+    assert!(file.bounds().1.is_finite());
+
+    for value in file.data.iter_mut() {
+        *value = f32::NAN;
+    }
+    assert_eq!(file.statistics(), None);
+    assert!(file.bounds().0.is_nan());
+}
+
+#[test]
+fn histogram() {
+    let file = synthetic_file(&[10, 13], &[4, 6]);
+    let histogram = file.histogram(13).unwrap();
+    assert_eq!(histogram.total(), 130);
+    // Bin width is 129 / 13, so the first bin contains 0..=9
+    assert_eq!(histogram.counts[0], 10);
+    assert_eq!(histogram.counts.iter().sum::<usize>(), 130);
+    assert_eq!(file.histogram(0), None);
+}
+
+#[test]
+fn percentiles() {
+    let file = synthetic_file(&[10, 13], &[4, 6]);
+    assert_eq!(file.percentile(0.0), Some(0.0));
+    assert_eq!(file.percentile(100.0), Some(129.0));
+    assert_float_eq!(file.percentile(50.0).unwrap(), 64.5, abs <= 1.0);
+
+    let histogram = file.histogram(4096).unwrap();
+    assert_float_eq!(histogram.percentile(25.0), 32.25, abs <= 1.0);
+}
+
+#[test]
+fn noise_level_robust_against_peaks() {
+    let mut file = synthetic_file(&[10, 13], &[4, 6]);
+    let noise = file.noise_level().unwrap();
+    // Median absolute deviation of 0..130 is 32.5
+    assert_float_eq!(noise, 32.5 * 1.4826, rel <= 1e-4);
+
+    // A few large peaks barely change the estimate
+    file.data[0] = 1e6;
+    file.data[1] = 1e6;
+    assert_float_eq!(file.noise_level().unwrap(), noise, rel <= 0.05);
+}
+
+#[test]
+fn noise_level_real_spectrum() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let statistics = file.statistics().unwrap();
+    let noise = file.noise_level().unwrap();
+    assert!(noise > 0.0);
+    assert!((noise as f64) < statistics.rms);
+    assert!(statistics.max > 10.0 * noise);
+}
+
+#[test]
+fn noise_level_exact_for_many_values() {
+    // More values than are collected at once, so the median is narrowed down via histograms
+    let file = synthetic_file(&[300, 301], &[32, 32]);
+    let center = (300 * 301 - 1) as f64 / 2.0;
+    let mut deviations: Vec<_> = (0..300 * 301).map(|v| (v as f64 - center).abs()).collect();
+    deviations.sort_by(|a, b| a.total_cmp(b));
+    let mad = (deviations[deviations.len() / 2 - 1] + deviations[deviations.len() / 2]) / 2.0;
+    assert_float_eq!(
+        file.noise_level().unwrap() as f64,
+        mad * 1.4826,
+        rel <= 1e-6
+    );
+}
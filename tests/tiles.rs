@@ -1,3 +1,4 @@
+use std::convert::TryInto;
 use std::ops::Range;
 
 use ucsf_nmr::{Tiles, UcsfFile};
@@ -43,15 +44,15 @@ fn correct_padded_tiles() {
     let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
 
     let (_, contents) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
-    assert_eq!(false, contents.axis_headers[0].tile_is_padded(0));
-    assert_eq!(false, contents.axis_headers[0].tile_is_padded(1));
-    assert_eq!(false, contents.axis_headers[0].tile_is_padded(2));
-    assert_eq!(false, contents.axis_headers[0].tile_is_padded(3));
-    assert_eq!(false, contents.axis_headers[1].tile_is_padded(0));
-    assert_eq!(false, contents.axis_headers[1].tile_is_padded(1));
-    assert_eq!(false, contents.axis_headers[1].tile_is_padded(2));
-    assert_eq!(false, contents.axis_headers[1].tile_is_padded(3));
-    assert_eq!(true, contents.axis_headers[1].tile_is_padded(4));
+    assert!(!contents.axis_headers[0].tile_is_padded(0));
+    assert!(!contents.axis_headers[0].tile_is_padded(1));
+    assert!(!contents.axis_headers[0].tile_is_padded(2));
+    assert!(!contents.axis_headers[0].tile_is_padded(3));
+    assert!(!contents.axis_headers[1].tile_is_padded(0));
+    assert!(!contents.axis_headers[1].tile_is_padded(1));
+    assert!(!contents.axis_headers[1].tile_is_padded(2));
+    assert!(!contents.axis_headers[1].tile_is_padded(3));
+    assert!(contents.axis_headers[1].tile_is_padded(4));
 
     assert_eq!(0, contents.axis_headers[0].tile_padding(0));
     assert_eq!(0, contents.axis_headers[0].tile_padding(1));
@@ -159,3 +160,42 @@ fn correct_tiles_padding() {
     assert_absolute_pos(&mut tiles, (128, 64));
     assert_absolute_pos(&mut tiles, (128, 1));
 }
+
+#[test]
+fn correct_tiles_data_padded() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+
+    // Tiles are 128x64 points, stored row-major with 5 tiles along the second axis.
+    // Each tile (including the padded ones) occupies the full 128 * 64 points in the file.
+    let raw_value = |i_axis_1: usize, i_axis_2: usize| {
+        let tile_n = (i_axis_1 / 128) * 5 + i_axis_2 / 64;
+        let in_tile = (i_axis_1 % 128) * 64 + i_axis_2 % 64;
+        let offset = 180 + 2 * 128 + 4 * (tile_n * 128 * 64 + in_tile);
+        f32::from_be_bytes(contents[offset..offset + 4].try_into().unwrap())
+    };
+
+    let mut count = 0;
+    for tile in file.tiles() {
+        assert_eq!(tile.data().len(), 128 * 64);
+        for ((i_axis_1, i_axis_2), value) in tile.iter_with_abolute_pos().as_2d() {
+            assert!(i_axis_1 < 512);
+            assert!(i_axis_2 < 257);
+            assert_eq!(raw_value(i_axis_1, i_axis_2).to_bits(), value.to_bits());
+            count += 1;
+        }
+    }
+    assert_eq!(count, 512 * 257);
+
+    let data = file.data_continous();
+    assert_eq!(raw_value(0, 256).to_bits(), data[256].to_bits());
+    assert_eq!(
+        raw_value(200, 100).to_bits(),
+        data[200 * 257 + 100].to_bits()
+    );
+    assert_eq!(
+        raw_value(511, 256).to_bits(),
+        data[511 * 257 + 256].to_bits()
+    );
+}
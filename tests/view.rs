@@ -0,0 +1,85 @@
+use ucsf_nmr::{UcsfFile, UcsfView};
+
+fn assert_same_tiles(contents: &[u8]) {
+    let (_, file) = UcsfFile::parse(contents).expect("Failed parsing");
+    let (rem, view) = UcsfView::parse(contents).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(view.header, file.header);
+    assert_eq!(view.axis_headers, file.axis_headers);
+    assert_eq!(view.axis_tiles(), file.axis_tiles());
+    assert_eq!(view.axis_tile_sizes(), file.axis_tile_sizes());
+    assert_eq!(view.axis_sizes(), file.axis_sizes());
+    assert_eq!(view.tiles().count(), file.tiles().count());
+
+    for (tile, view_tile) in file.tiles().zip(view.tiles()) {
+        assert_eq!(tile.axis_lengths, view_tile.axis_lengths);
+        assert_eq!(tile.axis_starts, view_tile.axis_starts);
+        assert_eq!(tile.padded_axis_lengths, view_tile.padded_axis_lengths);
+        assert_eq!(tile.data(), &view_tile.to_owned_tile().data[..]);
+        assert!(tile
+            .iter_with_abolute_pos()
+            .eq(view_tile.iter_with_abolute_pos()));
+
+        let mut values = tile.iter_with_abolute_pos();
+        let mut view_values = view_tile.iter_with_abolute_pos();
+        while let Some((pos, value)) = values.next_with_pos() {
+            assert_eq!(view_values.next_with_pos(), Some((pos, value)));
+        }
+        assert_eq!(view_values.next_with_pos(), None);
+    }
+}
+
+#[test]
+fn view_tiles() {
+    assert_same_tiles(include_bytes!("./data/15n_hsqc.ucsf"));
+}
+
+#[test]
+fn view_tiles_padded() {
+    assert_same_tiles(include_bytes!("./data/Nhsqc_highres_600MHz.ucsf"));
+}
+
+#[test]
+fn view_single_values() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let (_, view) = UcsfView::parse(&contents[..]).expect("Failed parsing");
+
+    let tile = view.tile(7).unwrap();
+    let file_tile = file.tiles().nth(7).unwrap();
+    assert_eq!(tile.value(0), file_tile.data()[0]);
+    assert_eq!(tile.value(4321), file_tile.data()[4321]);
+    assert_eq!(tile.imaginary_value(0), None);
+    assert!(view.tile(20).is_none());
+}
+
+#[test]
+fn view_borrows_bytes() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+    let (_, view) = UcsfView::parse(&contents[..]).expect("Failed parsing");
+
+    assert_eq!(view.raw_data().as_ptr(), contents[436..].as_ptr());
+    assert_eq!(view.tile(1).unwrap().raw_data().len(), 128 * 176 * 4);
+}
+
+#[test]
+fn view_to_file() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+    let (_, mut file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    file.header.components = 2;
+    file.imaginary_data = Some(file.data.iter().map(|value| value * 0.5).collect());
+    let contents = file.to_bytes();
+
+    let (_, view) = UcsfView::parse(&contents).expect("Failed parsing");
+    let tile = view.tile(2).unwrap();
+    let file_tile = file.tiles().nth(2).unwrap();
+    assert_eq!(tile.value(10), file_tile.data()[10]);
+    assert_eq!(
+        tile.imaginary_value(10),
+        Some(file_tile.imaginary_data().unwrap()[10])
+    );
+
+    let view_file = view.to_file();
+    assert_eq!(view_file.data, file.data);
+    assert_eq!(view_file.imaginary_data, file.imaginary_data);
+}
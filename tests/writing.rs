@@ -0,0 +1,94 @@
+use ucsf_nmr::{AxisHeader, Header, UcsfFile};
+
+#[test]
+fn roundtrip_file() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(&contents[..], &file.to_bytes()[..]);
+}
+
+#[test]
+fn roundtrip_file_padded() {
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(&contents[..], &file.to_bytes()[..]);
+}
+
+#[test]
+fn roundtrip_header() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, header) = Header::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(&contents[0..180], &header.to_bytes()[..]);
+}
+
+#[test]
+fn roundtrip_axis_header() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, header) = AxisHeader::parse(&contents[180..]).expect("Failed parsing");
+    assert_eq!(&contents[180..308], &header.to_bytes()[..]);
+    let (_, header) = AxisHeader::parse(&contents[308..]).expect("Failed parsing");
+    assert_eq!(&contents[308..436], &header.to_bytes()[..]);
+}
+
+#[test]
+fn write_to_writer() {
+    let contents = include_bytes!("./data/15n_hsqc.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let mut written = vec![];
+    file.write(&mut written).expect("Failed writing");
+
+    let (rem, reparsed) = UcsfFile::parse(&written).expect("Failed parsing");
+    assert_eq!(rem.len(), 0);
+    assert_eq!(file.header, reparsed.header);
+    assert_eq!(file.axis_headers, reparsed.axis_headers);
+    assert_eq!(file.data, reparsed.data);
+}
+
+#[test]
+fn write_tile_by_tile() {
+    /// Writer that records the size of the largest single write.
+    #[derive(Default)]
+    struct LargestWrite {
+        written: usize,
+        largest: usize,
+    }
+
+    impl std::io::Write for LargestWrite {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.written += buf.len();
+            self.largest = self.largest.max(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let contents = include_bytes!("./data/Nhsqc_highres_600MHz.ucsf");
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    let mut writer = LargestWrite::default();
+    file.write(&mut writer).expect("Failed writing");
+
+    let tile_bytes = file.axis_tile_sizes().iter().product::<usize>() * 4;
+    assert_eq!(writer.written, contents.len());
+    assert!(file.tiles().count() > 1);
+    assert_eq!(writer.largest, tile_bytes.max(180));
+}
+
+#[test]
+fn roundtrip_unknown_axis_bytes() {
+    let mut contents = include_bytes!("./data/15n_hsqc.ucsf").to_vec();
+    // Bytes 12-15 of the first axis header don't contain the number of data points
+    contents[180 + 12..180 + 16].copy_from_slice(&[1, 2, 3, 4]);
+
+    let (_, file) = UcsfFile::parse(&contents[..]).expect("Failed parsing");
+    assert_eq!(file.axis_headers[0].unknown, [1, 2, 3, 4]);
+    assert_eq!(&contents[..], &file.to_bytes()[..]);
+}